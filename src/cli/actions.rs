@@ -1,8 +1,8 @@
 
 use clap::Subcommand;
+use clap_complete::Shell;
 use std::path::PathBuf;
 
-use crate::session::theme::Theme;
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -12,6 +12,30 @@ pub enum Commands {
         
         #[arg(short, long)]
         restore: Option<String>,
+
+        #[arg(short, long)]
+        persona: Option<String>,
+    },
+
+    // 全屏界面
+    Tui,
+
+    // 单次提问：提示词之后附加标准输入的内容，回复只写到标准输出，
+    // 例如 git diff | mobius ask "review this"
+    Ask {
+        prompt: Vec<String>,
+
+        #[arg(short, long)]
+        session: Option<String>,
+
+        // 使用临时会话，不保存
+        #[arg(long)]
+        no_save: bool,
+    },
+
+    Persona {
+        #[command(subcommand)]
+        subcommand: PersonaSubcommand,
     },
     
     Config {
@@ -30,6 +54,20 @@ pub enum Commands {
         
         #[arg(short, long)]
         all: bool,
+
+        #[arg(long)]
+        tag: Option<String>,
+
+        #[arg(long)]
+        folder: Option<String>,
+
+        #[arg(long)]
+        pinned: bool,
+    },
+
+    Tag {
+        #[command(subcommand)]
+        subcommand: TagSubcommand,
     },
     
     Resume,
@@ -55,6 +93,30 @@ pub enum Commands {
     Import {
         path: PathBuf,
     },
+
+    Trash {
+        #[command(subcommand)]
+        subcommand: TrashSubcommand,
+    },
+
+    // 为项目建立本地检索索引，配合 /rag 使用
+    Index {
+        dir: PathBuf,
+    },
+
+    // 支持 "短语"、role:user、after:YYYY-MM-DD、before:YYYY-MM-DD
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    // 生成 shell 补全脚本，例如 mobius completions bash > /etc/bash_completion.d/mobius
+    Completions {
+        shell: Shell,
+    },
 }
 
 #[derive(Subcommand)]
@@ -64,16 +126,108 @@ pub enum ConfigSubcommand {
     SetMaxSessions {
         max: usize,
     },
+
+    // 0 表示不限制
+    SetMaxAge {
+        days: i64,
+    },
+
+    // 0 表示不限制
+    SetMaxSize {
+        mb: u64,
+    },
     
     SetModel {
         model: String,
     },
     
+    // light、dark、system 或 custom_themes 中定义的主题名称
     SetTheme {
-        theme: Theme,
+        theme: String,
     },
     
     ToggleAutoSave,
+
+    // zh-CN、en，或 auto 跟随 LANG 等环境变量
+    SetLanguage {
+        language: String,
+    },
+
+    // 点号分隔的路径，例如 default_model.api_url、models.0.name
+    Get {
+        key: String,
+
+        // 原样显示 api_key，默认只显示首尾几个字符
+        #[arg(long)]
+        reveal: bool,
+    },
+
+    // 值先按 JSON 解析，解析失败时作为字符串
+    Set {
+        key: String,
+        value: String,
+    },
+
+    // 必填项恢复为默认值
+    Unset {
+        key: String,
+    },
+
+    // 用 $EDITOR 编辑配置文件，保存时检查格式
+    Edit,
+
+    // 检查配置文件，有错误时可用默认值修复
+    Check,
+
+    Model {
+        #[command(subcommand)]
+        subcommand: ModelSubcommand,
+    },
+}
+
+// 只在解析命令行时构造一次，不必为变体大小装箱
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+pub enum ModelSubcommand {
+    List,
+
+    // 未指定 --api-url 时沿用默认模型的地址；
+    // 未指定 --api-key、--api-key-env、--api-key-file、--api-key-cmd 时沿用默认模型的 key 设置
+    Add {
+        name: String,
+
+        // 接口中的模型名称，默认与 name 相同
+        #[arg(short, long)]
+        model: Option<String>,
+
+        #[arg(long)]
+        api_url: Option<String>,
+
+        #[arg(long)]
+        api_key: Option<String>,
+
+        #[arg(long)]
+        api_key_env: Option<String>,
+
+        #[arg(long)]
+        api_key_file: Option<PathBuf>,
+
+        #[arg(long)]
+        api_key_cmd: Option<String>,
+
+        #[arg(long)]
+        description: Option<String>,
+
+        #[arg(long)]
+        provider: Option<String>,
+
+        #[arg(long)]
+        context_window: Option<usize>,
+    },
+
+    Remove {
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -86,6 +240,100 @@ pub enum McpSubcommand {
     Delete {
         session_id: String,
     },
+
+    Pin {
+        session_id: String,
+    },
+
+    Unpin {
+        session_id: String,
+    },
+
+    // 不指定目录时移出目录
+    Move {
+        session_id: String,
+        folder: Option<String>,
+    },
     
-    Cleanup,
+    Cleanup {
+        // 只列出将被清理的会话，不实际移除
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+
+#[derive(Subcommand)]
+pub enum TrashSubcommand {
+    List,
+
+    Restore {
+        session_id: String,
+    },
+
+    Empty,
+}
+
+#[derive(Subcommand)]
+pub enum TagSubcommand {
+    Add {
+        session_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    Remove {
+        session_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    List,
 }
+
+#[derive(Subcommand)]
+pub enum PersonaSubcommand {
+    List,
+
+    // 未指定 --prompt 时打开编辑器输入系统提示词
+    Add {
+        name: String,
+
+        #[arg(short, long)]
+        prompt: Option<String>,
+
+        #[arg(short, long)]
+        model: Option<String>,
+
+        #[arg(long)]
+        temperature: Option<f32>,
+
+        #[arg(long)]
+        top_p: Option<f32>,
+    },
+
+    // 未指定任何选项时打开编辑器修改系统提示词
+    Edit {
+        name: String,
+
+        #[arg(short, long)]
+        prompt: Option<String>,
+
+        #[arg(short, long)]
+        model: Option<String>,
+
+        #[arg(long)]
+        temperature: Option<f32>,
+
+        #[arg(long)]
+        top_p: Option<f32>,
+    },
+
+    Remove {
+        name: String,
+    },
+
+    SetDefault {
+        name: String,
+    },
+}
\ No newline at end of file
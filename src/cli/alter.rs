@@ -1,18 +1,52 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
+use crossterm::style::StyledContent;
+use inquire::{Select, Text};
+use serde_json::{json, Value};
 use std::error::Error;
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Read};
+use std::process;
+use std::path::{Path, PathBuf};
 
+use crate::cli::editor::edit_text;
+use crate::cli::help::localize;
+use crate::cli::output::{
+    exit_code, flatten, print_error, print_json, print_plain, report, to_value, CommandError, OutputFormat, SessionSummary,
+};
+use crate::context::files::collect_attachments;
+use crate::context::project::{canonical_root, estimate_tokens};
+use crate::markdown::render::{render_markdown, terminal_width, StreamRenderer};
+use crate::context::rag::{Bm25Index, RetrievalSettings, DEFAULT_TOP_K};
+use crate::models::model::{generate_response, generate_response_with};
+use crate::search::{search, SearchQuery};
 use crate::session::main_loop::main_loop;
-use crate::session::manager::{SessionManager};
+use crate::session::manager::{Session, SessionError, SessionFilter, SessionManager};
 
-use crate::cli::actions::{Commands, ConfigSubcommand, McpSubcommand};
+use crate::cli::actions::{
+    Commands, ConfigSubcommand, McpSubcommand, ModelSubcommand, PersonaSubcommand, TagSubcommand,
+    TrashSubcommand,
+};
+use crate::session::config::{Config, Model, Persona, DEFAULT_PERSONA};
+use crate::session::template::{parse_assignments, render, variables, TemplateLibrary};
+use crate::session::archive::ArchiveReason;
+use crate::session::validate::{check_change, check_config, repair_config, Issue, Severity};
+use crate::i18n::{self, Locale, t};
+use crate::session::theme::{self, error_label, notice, warning_label, Theme};
+use crate::tui::run_tui;
 
 #[derive(Parser)]
-#[command(name = "会话管理")]
+#[command(name = "mobius")]
 #[command(version = "1.0")]
-#[command(about = "一个会话管理系统", long_about = None)]
+#[command(long_about = None)]
 pub struct Cli {
+    // 原样输出回复，不渲染 markdown
+    #[arg(long, global = true)]
+    pub raw: bool,
+
+    // 命令结果的输出格式，供脚本读取
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -28,117 +62,633 @@ impl Alter {
         let config_dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("session_manager");
+        let config_path = config_dir.join("config.json");
+
+        // 帮助信息也按所选语言显示，需要在解析命令行之前确定语言
+        i18n::init(&config_path);
+        let matches = localize(Cli::command()).get_matches();
+        let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        theme::init_styling(cli.output != OutputFormat::Table);
+
+        // 命令失败时按输出格式显示错误，并以非零状态退出
+        let output = cli.output;
+        if let Err(e) = self.execute(cli, &config_dir, config_path).await {
+            print_error(output, e.as_ref());
+            process::exit(exit_code(e.as_ref()));
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, cli: Cli, config_dir: &Path, config_path: PathBuf) -> Result<(), Box<dyn Error>> {
+        // 补全脚本不需要读取任何数据
+        if let Commands::Completions { shell } = cli.command {
+            clap_complete::generate(shell, &mut localize(Cli::command()), "mobius", &mut io::stdout());
+            return Ok(());
+        }
 
         if !config_dir.exists() {
-            fs::create_dir_all(&config_dir)?;
+            fs::create_dir_all(config_dir)?;
         }
 
-        let config_path = config_dir.join("config.json");
+        // 在加载配置之前检查，格式错误时指出具体的字段和行
+        if let Commands::Config { subcommand: ConfigSubcommand::Check } = cli.command {
+            return run_config_check(&config_path, cli.output);
+        }
+        let issues = check_config_file(&config_path)?;
+
         let sessions_path = config_dir.join("sessions.json");
 
         let mut session_manager = SessionManager::new(config_path)?;
+        session_manager.raw_output = cli.raw;
+        let output = cli.output;
+        theme::init(&session_manager.config);
+
+        // 修改配置的命令不重复提示，避免修复过程中反复出现；
+        // key 相关的警告只在会请求接口的命令中提示
+        if !matches!(cli.command, Commands::Config { .. }) {
+            let requests = calls_api(&cli.command);
+            for issue in issues.iter().filter(|issue| requests || !issue.is_request_only()) {
+                eprintln!("{} {}", severity_label(issue.severity), issue);
+            }
+        }
 
         if let Err(e) = session_manager.load_sessions(&sessions_path) {
-            eprintln!("警告: 无法加载会话数据: {}", e);
+            eprintln!("{} {}", warning_label(), t!("cli.load_sessions_failed", e));
         }
 
-        let cli = Cli::parse();
+        if let Err(e) = session_manager.load_state() {
+            eprintln!("{} {}", warning_label(), t!("cli.load_state_failed", e));
+        }
 
         match cli.command {
-            Commands::Start { title, restore } => {
+            Commands::Start {
+                title,
+                restore,
+                persona,
+            } => {
                 if let Some(session_id) = restore {
-                    if session_manager.switch_session(&session_id).is_ok() {
-                        println!("已恢复会话: {}", session_id);
-                    } else {
-                        println!("会话未找到: {}", session_id);
-                        let new_id =
-                            session_manager.create_session(title.as_deref().unwrap_or("新会话"));
-                        println!("已创建新会话: {}", new_id);
+                    let resolved = session_manager
+                        .resolve_session(&session_id)
+                        .and_then(|id| session_manager.switch_session(&id).map(|_| id));
+                    match resolved {
+                        Ok(id) => println!("{}", t!("cli.session_restored", id)),
+                        Err(e) => {
+                            eprintln!("{} {}", error_label(), e);
+                            let new_id =
+                                session_manager.create_session(title.as_deref());
+                            println!("{}", t!("cli.session_created", new_id));
+                        }
                     }
-                } else if session_manager.current_session_id.is_none() {
+                } else if title.is_none()
+                    && let Some(session_id) = &session_manager.current_session_id
+                {
+                    // 只有未指定 --title 和 --restore 时才继续上一次会话
+                    println!("{}", t!("cli.session_continued", session_id));
+                } else {
                     let new_id =
-                        session_manager.create_session(title.as_deref().unwrap_or("新会话"));
-                    println!("已创建新会话: {}", new_id);
+                        session_manager.create_session(title.as_deref());
+                    println!("{}", t!("cli.session_created", new_id));
+                }
+
+                if let Some(name) = persona {
+                    session_manager.set_persona(&name)?;
+                    println!("{}", t!("persona.current", name));
                 }
 
                 main_loop(&mut session_manager, &sessions_path).await?;
             }
 
+            Commands::Tui => {
+                run_tui(&mut session_manager, &sessions_path).await?;
+            }
+
+            Commands::Ask {
+                prompt,
+                session,
+                no_save,
+            } => {
+                run_ask(&mut session_manager, &sessions_path, &prompt.join(" "), session.as_deref(), no_save).await?;
+            }
+
+            Commands::Persona { subcommand } => match subcommand {
+                PersonaSubcommand::List => {
+                    let default = session_manager
+                        .config
+                        .default_persona
+                        .as_deref()
+                        .unwrap_or(DEFAULT_PERSONA);
+                    let personas = &session_manager.config.personas;
+                    match output {
+                        OutputFormat::Json => {
+                            let mut values = Vec::new();
+                            for persona in personas {
+                                let mut value = to_value(persona)?;
+                                value["default"] = json!(persona.name == default);
+                                values.push(value);
+                            }
+                            print_json(&values)?;
+                            return Ok(());
+                        }
+                        OutputFormat::Plain => {
+                            for persona in personas {
+                                print_plain(&[
+                                    persona.name.clone(),
+                                    persona.model.clone().unwrap_or_default(),
+                                    persona.temperature.map(|t| t.to_string()).unwrap_or_default(),
+                                    persona.top_p.map(|p| p.to_string()).unwrap_or_default(),
+                                    (persona.name == default).to_string(),
+                                ]);
+                            }
+                            return Ok(());
+                        }
+                        OutputFormat::Table => {}
+                    }
+                    for persona in personas {
+                        println!(
+                            "{}",
+                            t!(
+                                "persona.list_entry",
+                                persona.name,
+                                if persona.name == default { t!("persona.default_marker") } else { "" },
+                                persona.model.as_deref().unwrap_or(t!("common.default")),
+                                format!("{:?}", persona.temperature),
+                                format!("{:?}", persona.top_p)
+                            )
+                        );
+                        println!("  {}", preview(&persona.system_prompt, 60));
+                    }
+                }
+
+                PersonaSubcommand::Add {
+                    name,
+                    prompt,
+                    model,
+                    temperature,
+                    top_p,
+                } => {
+                    if session_manager.config.find_persona(&name).is_some() {
+                        return Err(CommandError::new("persona_exists", t!("persona.exists", name)).into());
+                    }
+                    let system_prompt = match prompt {
+                        Some(prompt) => prompt,
+                        None => edit_text("", "md")?.trim().to_string(),
+                    };
+                    session_manager.config.personas.push(Persona {
+                        name: name.clone(),
+                        system_prompt,
+                        model,
+                        temperature,
+                        top_p,
+                    });
+                    session_manager.save_config()?;
+                    report(output, &t!("persona.added", name), json!({ "name": name }))?;
+                }
+
+                PersonaSubcommand::Edit {
+                    name,
+                    prompt,
+                    model,
+                    temperature,
+                    top_p,
+                } => {
+                    let persona = session_manager.config.personas.iter_mut().find(|p| p.name == name);
+                    let Some(persona) = persona else {
+                        return Err(SessionError::PersonaNotFound(name).into());
+                    };
+                    let open_editor =
+                        prompt.is_none() && model.is_none() && temperature.is_none() && top_p.is_none();
+                    if open_editor {
+                        persona.system_prompt =
+                            edit_text(&persona.system_prompt, "md")?.trim().to_string();
+                    }
+                    if let Some(prompt) = prompt {
+                        persona.system_prompt = prompt;
+                    }
+                    if model.is_some() {
+                        persona.model = model;
+                    }
+                    if temperature.is_some() {
+                        persona.temperature = temperature;
+                    }
+                    if top_p.is_some() {
+                        persona.top_p = top_p;
+                    }
+                    session_manager.save_config()?;
+                    report(output, &t!("persona.updated", name), json!({ "name": name }))?;
+                }
+
+                PersonaSubcommand::Remove { name } => {
+                    let before = session_manager.config.personas.len();
+                    session_manager.config.personas.retain(|p| p.name != name);
+                    if session_manager.config.personas.len() == before {
+                        return Err(SessionError::PersonaNotFound(name).into());
+                    }
+                    if session_manager.config.default_persona.as_deref() == Some(name.as_str()) {
+                        session_manager.config.default_persona = None;
+                    }
+                    session_manager.save_config()?;
+                    report(output, &t!("persona.removed", name), json!({ "name": name }))?;
+                }
+
+                PersonaSubcommand::SetDefault { name } => {
+                    if session_manager.config.find_persona(&name).is_none() {
+                        return Err(SessionError::PersonaNotFound(name).into());
+                    }
+                    session_manager.config.default_persona = Some(name.clone());
+                    session_manager.save_config()?;
+                    report(output, &t!("persona.default_set", name), json!({ "default_persona": name }))?;
+                }
+            },
+
             Commands::Config { subcommand } => match subcommand {
                 ConfigSubcommand::Show => {
-                    println!("当前配置:");
-                    println!("  最大会话数: {}", session_manager.config.max_sessions);
-                    println!("  自动保存: {}", session_manager.config.auto_save);
-                    println!("  默认模型: {}", session_manager.config.default_model.name.unwrap());
-                    println!("  主题: {:?}", session_manager.config.theme);
+                    let config = &session_manager.config;
+                    match output {
+                        OutputFormat::Json => {
+                            print_json(&config.redacted())?;
+                            return Ok(());
+                        }
+                        OutputFormat::Plain => {
+                            for (key, value) in flatten(&config.redacted().to_json()?) {
+                                print_plain(&[key, value]);
+                            }
+                            return Ok(());
+                        }
+                        OutputFormat::Table => {}
+                    }
+                    println!("{}", t!("config.title"));
+                    println!("{}", t!("config.max_sessions", config.max_sessions));
+                    println!("{}", t!("config.max_age", format!("{:?}", config.max_session_age_days)));
+                    println!("{}", t!("config.max_size", format!("{:?}", config.max_sessions_size_mb)));
+                    println!("{}", t!("config.auto_save", config.auto_save));
+                    println!("{}", t!("config.default_model", config.default_model.name.as_deref().unwrap_or_default()));
+                    println!("{}", t!("config.api_key", config.default_model.api_key_label()));
+                    println!("{}", t!("config.theme", config.theme_label()));
+                    println!("{}", t!("config.language", language_label(config.language)));
                 }
 
                 ConfigSubcommand::SetMaxSessions { max } => {
                     session_manager.config.max_sessions = max;
                     session_manager.save_config()?;
-                    println!("最大会话数已设置为: {}", max);
+                    report(output, &t!("config.max_sessions_set", max), json!({ "max_sessions": max }))?;
+                }
+
+                ConfigSubcommand::SetMaxAge { days } => {
+                    session_manager.config.max_session_age_days = (days > 0).then_some(days);
+                    session_manager.save_config()?;
+                    report(
+                        output,
+                        &t!("config.max_age_set", days),
+                        json!({ "max_session_age_days": session_manager.config.max_session_age_days }),
+                    )?;
+                }
+
+                ConfigSubcommand::SetMaxSize { mb } => {
+                    session_manager.config.max_sessions_size_mb = (mb > 0).then_some(mb);
+                    session_manager.save_config()?;
+                    report(
+                        output,
+                        &t!("config.max_size_set", mb),
+                        json!({ "max_sessions_size_mb": session_manager.config.max_sessions_size_mb }),
+                    )?;
                 }
 
                 ConfigSubcommand::SetModel { model } => {
                     session_manager.config.default_model.name = Some(model);
                     session_manager.save_config()?;
-                    println!("默认模型已设置为: {:#?}", session_manager.config.default_model);
+                    let model = session_manager.config.default_model.redacted();
+                    report(output, &t!("config.model_set", model.display_name()), json!({ "default_model": model }))?;
                 }
 
                 ConfigSubcommand::SetTheme { theme } => {
-                    session_manager.config.theme = theme;
+                    let config = &mut session_manager.config;
+                    if let Ok(builtin) = Theme::from_str(&theme, true) {
+                        config.theme = builtin;
+                        config.custom_theme = None;
+                    } else if config.custom_themes.iter().any(|t| t.name == theme) {
+                        config.custom_theme = Some(theme.clone());
+                    } else {
+                        return Err(CommandError::new("unknown_theme", t!("config.unknown_theme", theme)).into());
+                    }
                     session_manager.save_config()?;
-                    println!("主题已设置为: {:?}", theme);
+                    let config = &session_manager.config;
+                    report(
+                        output,
+                        &t!("config.theme_set", config.theme_label()),
+                        json!({ "theme": config.theme, "custom_theme": config.custom_theme }),
+                    )?;
                 }
 
                 ConfigSubcommand::ToggleAutoSave => {
                     session_manager.config.auto_save = !session_manager.config.auto_save;
                     session_manager.save_config()?;
-                    println!(
-                        "自动保存已{}",
-                        if session_manager.config.auto_save {
-                            "启用"
-                        } else {
-                            "禁用"
-                        }
-                    );
+                    let auto_save = session_manager.config.auto_save;
+                    let message = if auto_save {
+                        t!("config.auto_save_enabled")
+                    } else {
+                        t!("config.auto_save_disabled")
+                    };
+                    report(output, message, json!({ "auto_save": auto_save }))?;
                 }
-            },
 
-            Commands::Generate { session_id } => match session_manager.switch_session(&session_id) {
-                Ok(_) => {
-                    println!("确定要生成改对话吗?: {}", session_id);
-                    let _ = session_manager.generate_session_file(Some(&session_id)).await;
+                ConfigSubcommand::SetLanguage { language } => {
+                    let locale = match language.as_str() {
+                        "auto" => None,
+                        other => match Locale::parse(other) {
+                            Some(locale) => Some(locale),
+                            None => {
+                                return Err(CommandError::new("unknown_language", t!("config.unknown_language", other)).into());
+                            }
+                        },
+                    };
+                    session_manager.config.language = locale;
+                    session_manager.save_config()?;
+                    let message = match locale {
+                        Some(locale) => t!("config.language_set", locale),
+                        None => t!("config.language_cleared").to_string(),
+                    };
+                    report(output, &message, json!({ "language": locale }))?;
                 }
-                Err(e) => eprintln!("错误: {}", e),
+
+                ConfigSubcommand::Get { key, reveal } => match displayed_config(&session_manager.config, reveal).get_value(&key) {
+                    Some(value) if output == OutputFormat::Json => print_json(&value)?,
+                    Some(Value::String(text)) => println!("{}", text),
+                    Some(value) => println!("{}", serde_json::to_string_pretty(&value)?),
+                    None => return Err(CommandError::new("unknown_key", t!("config.unknown_key", key)).into()),
+                },
+
+                ConfigSubcommand::Set { key, value } => match session_manager.config.set_value(&key, &value) {
+                    Ok(config) => {
+                        session_manager.config = checked_change(&session_manager.config, config)?;
+                        session_manager.save_config()?;
+                        let value = session_manager.config.redacted().get_value(&key).unwrap_or_default();
+                        report(
+                            output,
+                            &t!("config.value_set", key, value_label(&value)),
+                            json!({ "key": key, "value": value }),
+                        )?;
+                    }
+                    Err(e) => return Err(CommandError::new("invalid_value", format!("{:#}", e)).into()),
+                },
+
+                ConfigSubcommand::Unset { key } => match session_manager.config.unset_value(&key) {
+                    Ok(config) => {
+                        session_manager.config = checked_change(&session_manager.config, config)?;
+                        session_manager.save_config()?;
+                        let value = session_manager.config.redacted().get_value(&key).unwrap_or_default();
+                        report(
+                            output,
+                            &t!("config.value_unset", key, value_label(&value)),
+                            json!({ "key": key, "value": value }),
+                        )?;
+                    }
+                    Err(e) => return Err(CommandError::new("invalid_value", format!("{:#}", e)).into()),
+                },
+
+                ConfigSubcommand::Edit => {
+                    // 编辑配置文件原文，保留其中的格式
+                    let mut content = fs::read_to_string(&session_manager.config_path)
+                        .or_else(|_| serde_json::to_string_pretty(&session_manager.config))?;
+                    loop {
+                        content = edit_text(&content, "json")?;
+                        let (config, issues) = check_config(&content);
+                        for issue in &issues {
+                            eprintln!("{} {}", severity_label(issue.severity), issue);
+                        }
+                        match config {
+                            Some(config) => {
+                                fs::write(&session_manager.config_path, &content)?;
+                                session_manager.config = config;
+                                println!("{}", t!("config.saved"));
+                                break;
+                            }
+                            None => {
+                                let yes = t!("common.yes");
+                                let again = Select::new(t!("config.edit_again"), vec![yes, t!("common.no")]).prompt();
+                                if !matches!(again, Ok(choice) if choice == yes) {
+                                    println!("{}", t!("common.cancelled"));
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // 已在加载配置之前处理
+                ConfigSubcommand::Check => {}
+
+                ConfigSubcommand::Model { subcommand } => match subcommand {
+                    ModelSubcommand::List => {
+                        let config = &session_manager.config;
+                        let is_default = |model: &Model| std::ptr::eq(model, &config.default_model);
+                        match output {
+                            OutputFormat::Json => {
+                                let mut values = Vec::new();
+                                for model in config.all_models() {
+                                    let mut value = to_value(&model.redacted())?;
+                                    value["default"] = json!(is_default(model));
+                                    values.push(value);
+                                }
+                                print_json(&values)?;
+                            }
+                            OutputFormat::Plain => {
+                                for model in config.all_models() {
+                                    print_plain(&[
+                                        model.display_name(),
+                                        &model.model,
+                                        &model.api_url,
+                                        &is_default(model).to_string(),
+                                    ]);
+                                }
+                            }
+                            OutputFormat::Table => {
+                                for model in config.all_models() {
+                                    println!(
+                                        "{}",
+                                        t!(
+                                            "config.model_entry",
+                                            model.display_name(),
+                                            if is_default(model) { t!("config.model_default") } else { "" },
+                                            model.model,
+                                            model.api_url
+                                        )
+                                    );
+                                }
+                            }
+                        }
+                    }
+
+                    ModelSubcommand::Add {
+                        name,
+                        model,
+                        api_url,
+                        api_key,
+                        api_key_env,
+                        api_key_file,
+                        api_key_cmd,
+                        description,
+                        provider,
+                        context_window,
+                    } => {
+                        let config = &mut session_manager.config;
+                        if config.find_model(&name).is_some() {
+                            return Err(CommandError::new("model_exists", t!("config.model_exists", name)).into());
+                        }
+                        // 未指定任何 key 来源时沿用默认模型的全部 key 设置
+                        let default_model = &config.default_model;
+                        let inherit = api_key.is_none() && api_key_env.is_none() && api_key_file.is_none() && api_key_cmd.is_none();
+                        let (api_key, api_key_env, api_key_file, api_key_cmd) = if inherit {
+                            (
+                                default_model.api_key.clone(),
+                                default_model.api_key_env.clone(),
+                                default_model.api_key_file.clone(),
+                                default_model.api_key_cmd.clone(),
+                            )
+                        } else {
+                            (api_key.unwrap_or_default(), api_key_env, api_key_file, api_key_cmd)
+                        };
+                        let model = Model {
+                            name: Some(name.clone()),
+                            description,
+                            provider,
+                            api_key,
+                            api_key_env,
+                            api_key_file,
+                            api_key_cmd,
+                            api_url: api_url.unwrap_or_else(|| config.default_model.api_url.clone()),
+                            api_version: None,
+                            model: model.unwrap_or_else(|| name.clone()),
+                            context_window,
+                        };
+                        let mut updated = config.clone();
+                        updated.models.get_or_insert_with(Vec::new).push(model);
+                        session_manager.config = checked_change(&session_manager.config, updated)?;
+                        session_manager.save_config()?;
+                        report(output, &t!("config.model_added", name), json!({ "name": name }))?;
+                    }
+
+                    ModelSubcommand::Remove { name } => {
+                        let config = &mut session_manager.config;
+                        let matches = |m: &Model| m.name.as_deref() == Some(name.as_str()) || m.model == name;
+                        let models = config.models.get_or_insert_with(Vec::new);
+                        let before = models.len();
+                        models.retain(|m| !matches(m));
+                        let removed = models.len() < before;
+                        if models.is_empty() {
+                            config.models = None;
+                        }
+                        if !removed {
+                            if matches(&config.default_model) {
+                                return Err(CommandError::new("model_is_default", t!("config.model_is_default", name)).into());
+                            }
+                            return Err(SessionError::ModelNotFound(name).into());
+                        }
+                        session_manager.save_config()?;
+                        report(output, &t!("config.model_removed", name), json!({ "name": name }))?;
+                    }
+                },
             },
 
+            Commands::Generate { session_id } => {
+                let session_id = session_manager.resolve_session(&session_id)?;
+                session_manager.switch_session(&session_id)?;
+                println!("{}", t!("cli.generate_confirm", session_id));
+                let _ = session_manager.generate_session_file(Some(&session_id)).await;
+            }
+
             Commands::Mcp { subcommand } => {
                 match subcommand {
                     McpSubcommand::Rename {
                         session_id,
                         new_title,
-                    } => match session_manager.rename_session(&session_id, &new_title) {
-                        Ok(_) => println!("会话 '{}' 已重命名为 '{}'", session_id, new_title),
-                        Err(e) => eprintln!("错误: {}", e),
-                    },
+                    } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        session_manager.rename_session(&session_id, &new_title)?;
+                        report(
+                            output,
+                            &t!("session.renamed", session_id, new_title),
+                            json!({ "id": session_id, "title": new_title }),
+                        )?;
+                    }
 
                     McpSubcommand::Delete { session_id } => {
-                        match session_manager.remove_session(&session_id) {
-                            Ok(_) => println!("会话 '{}' 已删除", session_id),
-                            Err(e) => eprintln!("错误: {}", e),
-                        }
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        session_manager.remove_session(&session_id)?;
+                        report(
+                            output,
+                            &t!("session.trashed", session_id),
+                            json!({ "id": session_id, "reason": ArchiveReason::Deleted }),
+                        )?;
                     }
 
-                    McpSubcommand::Cleanup => {
-                        let old_count = session_manager.sessions.len();
-                        session_manager.cleanup_old_sessions();
-                        let new_count = session_manager.sessions.len();
-                        println!(
-                            "已清理 {} 个会话, 剩余 {} 个会话",
-                            old_count - new_count,
-                            new_count
+                    McpSubcommand::Pin { session_id } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        let session = session_manager.get_session_mut(&session_id)?;
+                        session.pinned = true;
+                        report(
+                            output,
+                            &t!("session.pinned", session_id),
+                            json!({ "id": session_id, "pinned": true }),
+                        )?;
+                    }
+
+                    McpSubcommand::Unpin { session_id } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        let session = session_manager.get_session_mut(&session_id)?;
+                        session.pinned = false;
+                        report(
+                            output,
+                            &t!("session.unpinned", session_id),
+                            json!({ "id": session_id, "pinned": false }),
+                        )?;
+                    }
+
+                    McpSubcommand::Move { session_id, folder } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        let session = session_manager.get_session_mut(&session_id)?;
+                        session.set_folder(folder.as_deref());
+                        let message = t!(
+                            "session.folder",
+                            session_id,
+                            session.folder.as_deref().unwrap_or(t!("common.none"))
                         );
+                        report(output, &message, json!({ "id": session_id, "folder": session.folder }))?;
+                    }
+
+                    McpSubcommand::Cleanup { dry_run } => {
+                        if dry_run {
+                            let planned = session_manager.plan_cleanup();
+                            if output != OutputFormat::Table {
+                                let current = session_manager.current_session_id.as_deref();
+                                let summaries: Vec<SessionSummary> = planned
+                                    .iter()
+                                    .map(|s| SessionSummary::new(session_index(&session_manager, &s.id), s, current == Some(s.id.as_str())))
+                                    .collect();
+                                print_summaries(output, &summaries)?;
+                                return Ok(());
+                            }
+                            println!("{}", t!("cleanup.plan", planned.len()));
+                            for session in planned {
+                                println!(
+                                    "{}",
+                                    t!(
+                                        "cleanup.entry",
+                                        session.title,
+                                        &session.id[..8],
+                                        session.last_accessed.format("%Y-%m-%d %H:%M:%S")
+                                    )
+                                );
+                            }
+                            return Ok(());
+                        }
+
+                        let removed = session_manager.cleanup_old_sessions();
+                        let remaining = session_manager.sessions.len();
+                        report(
+                            output,
+                            &t!("cleanup.done", removed.len(), remaining),
+                            json!({ "archived": removed, "remaining": remaining }),
+                        )?;
                     }
                 }
 
@@ -146,49 +696,78 @@ impl Alter {
                 session_manager.save_sessions(&sessions_path)?;
             }
 
-            Commands::List { detail, all } => {
-                let sessions = session_manager.list_sessions();
+            Commands::List {
+                detail,
+                all,
+                tag,
+                folder,
+                pinned,
+            } => {
+                let filter = SessionFilter { tag, folder, pinned };
+                let sessions = session_manager.filter_sessions(&filter);
+                let archived = if all {
+                    session_manager.list_archived()?
+                } else {
+                    Vec::new()
+                };
 
-                if sessions.is_empty() {
-                    println!("没有可用的会话");
+                if output != OutputFormat::Table {
+                    let current = session_manager.current_session_id.as_deref();
+                    let summaries: Vec<SessionSummary> = sessions
+                        .iter()
+                        .map(|s| SessionSummary::new(session_index(&session_manager, &s.id), s, current == Some(s.id.as_str())))
+                        .chain(archived.iter().enumerate().map(|(i, a)| SessionSummary::archived(i + 1, a)))
+                        .collect();
+                    print_summaries(output, &summaries)?;
                     return Ok(());
                 }
 
-                println!("{}会话:", if all { "所有 " } else { "" });
+                if sessions.is_empty() && archived.is_empty() {
+                    println!("{}", t!("list.empty"));
+                    return Ok(());
+                }
+
+                println!("{}", if all { t!("list.header_all") } else { t!("list.header") });
                 println!();
 
-                for (i, session) in sessions.iter().enumerate() {
+                // 序号始终对应未过滤的列表，可用 #序号 引用会话
+                let all_ids: Vec<&str> =
+                    session_manager.list_sessions().iter().map(|s| s.id.as_str()).collect();
+                for session in &sessions {
+                    let i = all_ids.iter().position(|id| *id == session.id).unwrap_or(0);
                     let current_indicator =
                         if Some(&session.id) == session_manager.current_session_id.as_ref() {
-                            " (当前)"
+                            t!("common.current")
                         } else {
                             ""
                         };
 
                     if detail {
                         println!(
-                            "{}. {} [ID: {}]{}",
+                            "{}. {} [ID: {}]{}{}",
                             i + 1,
                             session.title,
                             session.id,
-                            current_indicator
+                            current_indicator,
+                            session_labels(session)
                         );
                         println!(
-                            "  创建时间: {}",
-                            session.created_at.format("%Y-%m-%d %H:%M:%S")
+                            "{}",
+                            t!("list.created_at", session.created_at.format("%Y-%m-%d %H:%M:%S"))
                         );
                         println!(
-                            "  最后访问: {}",
-                            session.last_accessed.format("%Y-%m-%d %H:%M:%S")
+                            "{}",
+                            t!("list.last_accessed", session.last_accessed.format("%Y-%m-%d %H:%M:%S"))
                         );
-                        println!("  消息数量: {}", session.messages.len());
+                        println!("{}", t!("list.message_count", session.messages.len()));
                     } else {
                         println!(
-                            "{}. {} [ID: {}]{}",
+                            "{}. {} [ID: {}]{}{}",
                             i + 1,
                             session.title,
                             &session.id[..8],
-                            current_indicator
+                            current_indicator,
+                            session_labels(session)
                         );
                     }
 
@@ -196,27 +775,182 @@ impl Alter {
                         println!();
                     }
                 }
+
+                if !archived.is_empty() {
+                    println!("{}", t!("list.archived_header"));
+                    println!();
+                    for (i, archived) in archived.iter().enumerate() {
+                        println!(
+                            "{}. {} [ID: {}] ({} {})",
+                            i + 1,
+                            archived.session.title,
+                            &archived.session.id[..8],
+                            archive_reason_label(archived.reason),
+                            archived.archived_at.format("%Y-%m-%d %H:%M:%S")
+                        );
+                    }
+                }
+            }
+
+            Commands::Tag { subcommand } => {
+                match subcommand {
+                    TagSubcommand::Add { session_id, tags } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        let session = session_manager.get_session_mut(&session_id)?;
+                        session.add_tags(&tags);
+                        report(
+                            output,
+                            &t!("session.tags", session_id, session.tags.join(", ")),
+                            json!({ "id": session_id, "tags": session.tags }),
+                        )?;
+                    }
+
+                    TagSubcommand::Remove { session_id, tags } => {
+                        let session_id = session_manager.resolve_session(&session_id)?;
+                        let session = session_manager.get_session_mut(&session_id)?;
+                        session.remove_tags(&tags);
+                        report(
+                            output,
+                            &t!("session.tags", session_id, session.tags.join(", ")),
+                            json!({ "id": session_id, "tags": session.tags }),
+                        )?;
+                    }
+
+                    TagSubcommand::List => {
+                        let mut counts: Vec<(String, usize)> = Vec::new();
+                        for session in session_manager.sessions.values() {
+                            for tag in &session.tags {
+                                match counts.iter_mut().find(|(t, _)| t == tag) {
+                                    Some((_, count)) => *count += 1,
+                                    None => counts.push((tag.clone(), 1)),
+                                }
+                            }
+                        }
+                        counts.sort();
+                        match output {
+                            OutputFormat::Json => {
+                                let values: Vec<_> =
+                                    counts.iter().map(|(tag, count)| json!({ "tag": tag, "count": count })).collect();
+                                print_json(&values)?;
+                            }
+                            OutputFormat::Plain => {
+                                for (tag, count) in counts {
+                                    print_plain(&[tag, count.to_string()]);
+                                }
+                            }
+                            OutputFormat::Table => {
+                                if counts.is_empty() {
+                                    println!("{}", t!("tag.empty"));
+                                }
+                                for (tag, count) in counts {
+                                    println!("#{} ({})", tag, count);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                session_manager.save_sessions(&sessions_path)?;
+            }
+
+            Commands::Index { dir } => {
+                let root = dir.canonicalize()?;
+                if output == OutputFormat::Table {
+                    println!("{}", t!("index.building", root.display()));
+                }
+                let index = Bm25Index::build(&root);
+                let path = index.save(&session_manager.indexes_path)?;
+                report(
+                    output,
+                    &t!("index.done", index.file_count, index.chunks.len(), path.display()),
+                    json!({
+                        "root": root,
+                        "file_count": index.file_count,
+                        "chunk_count": index.chunks.len(),
+                        "path": path,
+                    }),
+                )?;
+            }
+
+            Commands::Search { query, limit } => {
+                run_search(&session_manager, &query.join(" "), limit, output)?;
+            }
+
+            // 已在加载数据之前处理
+            Commands::Completions { .. } => {}
+
+            Commands::Trash { subcommand } => {
+                match subcommand {
+                    TrashSubcommand::List => {
+                        let archived = session_manager.list_archived()?;
+                        if output != OutputFormat::Table {
+                            let summaries: Vec<SessionSummary> = archived
+                                .iter()
+                                .enumerate()
+                                .map(|(i, a)| SessionSummary::archived(i + 1, a))
+                                .collect();
+                            print_summaries(output, &summaries)?;
+                            return Ok(());
+                        }
+                        if archived.is_empty() {
+                            println!("{}", t!("trash.empty"));
+                            return Ok(());
+                        }
+                        for (i, archived) in archived.iter().enumerate() {
+                            println!(
+                                "{}",
+                                t!(
+                                    "trash.entry",
+                                    i + 1,
+                                    archived.session.title,
+                                    archived.session.id,
+                                    archive_reason_label(archived.reason),
+                                    archived.archived_at.format("%Y-%m-%d %H:%M:%S"),
+                                    archived.session.messages.len()
+                                )
+                            );
+                        }
+                    }
+
+                    TrashSubcommand::Restore { session_id } => {
+                        let session_id = session_manager.resolve_archived(&session_id)?;
+                        session_manager.restore_session(&session_id)?;
+                        report(output, &t!("session.restored", session_id), json!({ "id": session_id }))?;
+                    }
+
+                    TrashSubcommand::Empty => {
+                        let yes = t!("common.yes");
+                        let confirm = Select::new(t!("trash.confirm_empty"), vec![yes, t!("common.no")]).prompt();
+                        if let Ok(choice) = confirm
+                            && choice == yes
+                        {
+                            let count = session_manager.empty_trash()?;
+                            report(output, &t!("trash.emptied", count), json!({ "deleted": count }))?;
+                        }
+                    }
+                }
+
+                session_manager.save_sessions(&sessions_path)?;
             }
 
             Commands::Resume => {
                 if let Some(session_id) = &session_manager.current_session_id {
-                    println!("正在恢复上一次会话: {}", session_id);
+                    println!("{}", t!("cli.resuming", session_id));
                     main_loop(&mut session_manager, &sessions_path).await?;
                 } else {
-                    println!("没有可恢复的上一次会话");
-                    println!("正在创建新会话...");
-                    let _ = session_manager.create_session("新会话");
+                    println!("{}", t!("cli.nothing_to_resume"));
+                    println!("{}", t!("cli.creating_session"));
+                    let _ = session_manager.create_session(None);
                     main_loop(&mut session_manager, &sessions_path).await?;
                 }
             }
 
-            Commands::Restore { session_id } => match session_manager.switch_session(&session_id) {
-                Ok(_) => {
-                    println!("已切换到会话: {}", session_id);
-                    main_loop(&mut session_manager, &sessions_path).await?;
-                }
-                Err(e) => eprintln!("错误: {}", e),
-            },
+            Commands::Restore { session_id } => {
+                let session_id = session_manager.resolve_session(&session_id)?;
+                session_manager.switch_session(&session_id)?;
+                println!("{}", t!("cli.session_switched", session_id));
+                main_loop(&mut session_manager, &sessions_path).await?;
+            }
 
             Commands::Export {
                 path,
@@ -226,35 +960,37 @@ impl Alter {
                 if all {
                     let session_file = File::create(&path)?;
                     serde_json::to_writer_pretty(session_file, &session_manager.sessions)?;
-                    println!(
-                        "已导出 {} 个会话到 {}",
-                        session_manager.sessions.len(),
-                        path.display()
-                    );
+                    let ids: Vec<&String> = session_manager.sessions.keys().collect();
+                    report(
+                        output,
+                        &t!("export.all", ids.len(), path.display()),
+                        json!({ "path": path, "ids": ids }),
+                    )?;
                 } else if let Some(id) = session_id {
-                    if let Some(session) = session_manager.sessions.get(&id) {
-                        let session_file = File::create(&path)?;
-                        serde_json::to_writer_pretty(session_file, session)?;
-                        println!("已导出会话 '{}' 到 {}", id, path.display());
-                    } else {
-                        eprintln!("错误: 未找到会话 {}", id);
-                    }
+                    let id = session_manager.resolve_session(&id)?;
+                    let session = session_manager
+                        .sessions
+                        .get(&id)
+                        .ok_or_else(|| SessionError::SessionNotFound(id.clone()))?;
+                    let session_file = File::create(&path)?;
+                    serde_json::to_writer_pretty(session_file, &session.active_branch_session())?;
+                    report(output, &t!("export.session", id, path.display()), json!({ "path": path, "ids": [id] }))?;
                 } else {
-                    if let Some(current) = session_manager.current_session_id.as_ref() {
-                        if let Some(session) = session_manager.sessions.get(current) {
-                            let session_file = File::create(&path)?;
-                            serde_json::to_writer_pretty(session_file, session)?;
-                            println!("已导出当前会话到 {}", path.display());
-                        }
-                    } else {
-                        eprintln!("错误: 没有当前会话");
-                    }
+                    let session = session_manager
+                        .current_session_id
+                        .as_ref()
+                        .and_then(|current| session_manager.sessions.get(current))
+                        .ok_or_else(|| CommandError::new("no_current_session", t!("error.no_current_session")))?;
+                    let session_file = File::create(&path)?;
+                    serde_json::to_writer_pretty(session_file, &session.active_branch_session())?;
+                    report(output, &t!("export.current", path.display()), json!({ "path": path, "ids": [session.id] }))?;
                 }
             }
 
             Commands::Import { path } => {
                 session_manager.load_sessions(&path)?;
-                println!("已从 {} 导入会话", path.display());
+                let ids: Vec<&String> = session_manager.sessions.keys().collect();
+                report(output, &t!("import.done", path.display()), json!({ "path": path, "ids": ids }))?;
 
                 // 保存到默认位置
                 session_manager.save_sessions(&sessions_path)?;
@@ -265,7 +1001,7 @@ impl Alter {
     }
 }
 
-pub fn handle_command(
+pub async fn handle_command(
     command: &str,
     session_manager: &mut SessionManager,
     sessions_path: &PathBuf,
@@ -277,81 +1013,557 @@ pub fn handle_command(
 
     match parts[0] {
         "exit" => {
-            println!("已退出，会话已保存");
+            println!("{}", t!("repl.exit"));
             return Ok(true);
         }
 
         "list" => {
-            println!("\n--- 当前会话列表 ---");
+            println!("{}", t!("repl.list_header"));
             for (i, session) in session_manager.list_sessions().iter().enumerate() {
                 let current_indicator =
                     if Some(&session.id) == session_manager.current_session_id.as_ref() {
-                        " (当前)"
+                        t!("common.current")
                     } else {
                         ""
                     };
                 println!(
-                    "{}. {} [ID: {}]{}",
+                    "{}. {} [ID: {}]{}{}",
                     i + 1,
                     session.title,
                     &session.id[..8],
-                    current_indicator
+                    current_indicator,
+                    session_labels(session)
                 );
             }
         }
 
         "switch" if parts.len() > 1 => {
-            let session_id = parts[1];
-            match session_manager.switch_session(session_id) {
-                Ok(_) => {
-                    println!("已切换到会话: {}", session_id);
+            // `/switch -` 切换回上一个会话
+            let result = if parts[1] == "-" {
+                session_manager.switch_to_previous()
+            } else {
+                let query = command["switch".len()..].trim();
+                session_manager
+                    .resolve_session(query)
+                    .and_then(|id| session_manager.switch_session(&id).map(|_| id))
+            };
+
+            match result {
+                Ok(session_id) => {
+                    println!("{}", t!("cli.session_switched", session_id));
                     // 更新显示
-                    if let Some(session) = session_manager.sessions.get(session_id) {
-                        println!("当前会话: {} [ID: {}]", session.title, &session_id[..8]);
+                    if let Some(session) = session_manager.sessions.get(&session_id) {
+                        println!("{}", t!("repl.current_session", session.title, &session_id[..8]));
                     }
                 }
-                Err(e) => println!("错误: {}", e),
+                Err(e) => eprintln!("{} {}", error_label(), e),
             }
         }
 
         "new" => {
-            let title = parts.get(1).map(|s| *s).unwrap_or("新会话");
-            let new_id = session_manager.create_session(title);
-            println!("已创建新会话: {}", new_id);
-            println!("当前会话: {} [ID: {}]", title, &new_id[..8]);
+            let title = parts.get(1).copied();
+            let new_id = session_manager.create_session(title).to_string();
+            println!("{}", t!("cli.session_created", new_id));
+            if let Some(session) = session_manager.sessions.get(&new_id) {
+                println!("{}", t!("repl.current_session", session.title, &new_id[..8]));
+            }
         }
 
         "generate" => {
-            let session_id = parts.get(1).map(|s| *s);
-            let _ = session_manager.generate_session_file(session_id);
-            println!("对话文件已生成: {:?}", session_id);
+            let session_id = match parts.get(1) {
+                Some(query) => match session_manager.resolve_session(query) {
+                    Ok(id) => Some(id),
+                    Err(e) => {
+                        eprintln!("{} {}", error_label(), e);
+                        return Ok(false);
+                    }
+                },
+                None => None,
+            };
+            let _ = session_manager.generate_session_file(session_id.as_deref()).await;
+            println!("{}", t!("repl.generated", format!("{:?}", session_id)));
         }
 
         "save" => {
             session_manager.save_sessions(sessions_path)?;
-            println!("会话已手动保存");
+            println!("{}", t!("repl.saved"));
         }
 
         "rename" if parts.len() > 1 => {
             let new_title = parts[1..].join(" ");
             if let Some(session) = session_manager.get_current_session() {
                 session.update_title(&new_title);
-                println!("当前会话已重命名为: {}", new_title);
+                println!("{}", t!("repl.renamed", new_title));
             }
         }
 
         "title" => {
             if let Some(session) = session_manager.get_current_session() {
-                println!("当前会话标题: {}", session.title);
+                println!("{}", t!("repl.title", session.title));
+            }
+        }
+
+        "history" => {
+            if let Some(session) = session_manager.get_current_session() {
+                for (i, message) in session.active_branch().iter().enumerate() {
+                    println!("{}. [{}] {}", i + 1, message.role, preview(&message.content, 60));
+                }
+            }
+        }
+
+        "fork" => {
+            let index = match parts.get(1).map(|s| s.trim_start_matches('#').parse::<usize>()) {
+                Some(Ok(index)) => Some(index),
+                Some(Err(_)) => {
+                    eprintln!("{} {}", error_label(), t!("repl.invalid_index", parts[1]));
+                    return Ok(false);
+                }
+                None => None,
+            };
+            if let Some(session) = session_manager.get_current_session() {
+                match session.fork_at(index) {
+                    Ok(index) => println!("{}", t!("repl.forked", index)),
+                    Err(e) => eprintln!("{} {}", error_label(), e),
+                }
+            }
+        }
+
+        "branches" => {
+            if let Some(session) = session_manager.get_current_session() {
+                let head = session.head_id().map(str::to_string);
+                for (i, leaf) in session.leaves().iter().enumerate() {
+                    let branch = session.path_to(&leaf.id);
+                    let current_indicator = if head.as_deref() == Some(leaf.id.as_str()) {
+                        t!("common.current")
+                    } else {
+                        ""
+                    };
+                    let last_user = branch
+                        .iter()
+                        .rev()
+                        .find(|m| m.role == "user")
+                        .map(|m| preview(&m.content, 40))
+                        .unwrap_or_default();
+                    println!(
+                        "{}",
+                        t!("repl.branch_entry", i + 1, branch.len(), last_user, current_indicator)
+                    );
+                }
+            }
+        }
+
+        "checkout" if parts.len() > 1 => {
+            if let Some(session) = session_manager.get_current_session() {
+                let leaf_id = parts[1]
+                    .trim_start_matches('#')
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| session.leaves().get(i).map(|m| m.id.clone()));
+                match leaf_id {
+                    Some(leaf_id) => {
+                        session.checkout(&leaf_id)?;
+                        println!("{}", t!("repl.checked_out", parts[1]));
+                    }
+                    None => eprintln!("{} {}", error_label(), t!("repl.branch_not_found", parts[1])),
+                }
+            }
+        }
+
+        // 按当前终端宽度重新显示最后一条回复，调整窗口大小后可用来重新排版
+        "last" => {
+            let raw = session_manager.raw_output;
+            let Some(session) = session_manager.get_current_session() else {
+                return Ok(false);
+            };
+            match session.last_on_branch("assistant") {
+                Some(message) if raw || !theme::stdout_styled() => println!("{}", message.content),
+                Some(message) => {
+                    for line in render_markdown(&message.content, terminal_width()) {
+                        println!("{}", line);
+                    }
+                }
+                None => println!("{}", t!("repl.no_reply")),
+            }
+        }
+
+        "retry" => {
+            let Some(session) = session_manager.get_current_session() else {
+                return Ok(false);
+            };
+            // 最后一条是回复时退回到对应的提问，失败的请求则直接重试
+            let last = session
+                .active_branch()
+                .last()
+                .map(|m| (m.id.clone(), m.role == "assistant"));
+            if let Some((id, true)) = last {
+                session.rewind_before(&id)?;
+            }
+            if session.last_on_branch("user").is_none() {
+                eprintln!("{} {}", error_label(), t!("repl.nothing_to_retry"));
+                return Ok(false);
+            }
+            if let Err(e) = generate_response(session_manager).await {
+                eprintln!("{} {}", error_label(), e);
+            }
+        }
+
+        "edit" => {
+            let Some(session) = session_manager.get_current_session() else {
+                return Ok(false);
+            };
+            let Some(last_user) = session.last_on_branch("user").cloned() else {
+                eprintln!("{} {}", error_label(), t!("repl.nothing_to_edit"));
+                return Ok(false);
+            };
+            let edited = match edit_text(&last_user.content, "md") {
+                Ok(edited) => edited.trim().to_string(),
+                Err(e) => {
+                    eprintln!("{} {}", error_label(), t!("repl.editor_failed", e));
+                    return Ok(false);
+                }
+            };
+            if edited.is_empty() || edited == last_user.content {
+                println!("{}", t!("repl.unchanged"));
+                return Ok(false);
+            }
+            resend_edited(session_manager, &last_user.id, &edited).await?;
+        }
+
+        "undo" => {
+            if let Some(session) = session_manager.get_current_session() {
+                match session.last_on_branch("user").map(|m| m.id.clone()) {
+                    Some(id) => {
+                        session.rewind_before(&id)?;
+                        println!("{}", t!("repl.undone"));
+                    }
+                    None => eprintln!("{} {}", error_label(), t!("repl.nothing_to_undo")),
+                }
+            }
+        }
+
+        "alternatives" => {
+            if let Some(session) = session_manager.get_current_session() {
+                let branch = session.active_branch();
+                // 默认查看最后一条回复的其他版本
+                let target = match parts.get(1) {
+                    Some(index) => index
+                        .trim_start_matches('#')
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| i.checked_sub(1))
+                        .and_then(|i| branch.get(i).copied()),
+                    None => branch.iter().rev().find(|m| m.role == "assistant").copied(),
+                };
+                let Some(target) = target else {
+                    eprintln!("{} {}", error_label(), t!("repl.message_not_found"));
+                    return Ok(false);
+                };
+                let alternatives = session.alternatives(&target.id);
+                println!("{}", t!("repl.versions", alternatives.len()));
+                for (i, message) in alternatives.iter().enumerate() {
+                    let current_indicator = if message.id == target.id { t!("common.current") } else { "" };
+                    println!("{}", t!("repl.version_header", i + 1, current_indicator));
+                    println!("{}", message.content);
+                }
+            }
+        }
+
+        "tag" => {
+            if let Some(session) = session_manager.get_current_session() {
+                let tags: Vec<String> = parts.iter().skip(2).map(|s| s.to_string()).collect();
+                match parts.get(1).copied() {
+                    Some("add") if !tags.is_empty() => session.add_tags(&tags),
+                    Some("remove") if !tags.is_empty() => session.remove_tags(&tags),
+                    None => {}
+                    Some(_) => {
+                        println!("{}", t!("repl.tag_usage"));
+                        return Ok(false);
+                    }
+                }
+                println!("{}", t!("repl.tags", session.tags.join(", ")));
+            }
+        }
+
+        "pin" | "unpin" => {
+            if let Some(session) = session_manager.get_current_session() {
+                session.pinned = parts[0] == "pin";
+                println!("{}", if session.pinned { t!("repl.pinned") } else { t!("repl.unpinned") });
+            }
+        }
+
+        "folder" => {
+            if let Some(session) = session_manager.get_current_session() {
+                if parts.len() > 1 {
+                    session.set_folder(Some(&parts[1..].join(" ")));
+                }
+                println!("{}", t!("repl.folder", session.folder.as_deref().unwrap_or(t!("common.none"))));
+            }
+        }
+
+        "file" => {
+            match parts.get(1).copied() {
+                None => {
+                    if session_manager.pending_attachments.is_empty() {
+                        println!("{}", t!("repl.no_attachments"));
+                    }
+                    for attachment in &session_manager.pending_attachments {
+                        println!("{}", t!("repl.attachment_entry", attachment.path.display(), attachment.content.len()));
+                    }
+                }
+                Some("clear") => {
+                    session_manager.pending_attachments.clear();
+                    println!("{}", t!("repl.attachments_cleared"));
+                }
+                Some(_) => {
+                    let patterns: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
+                    let (attachments, warnings) = collect_attachments(&patterns);
+                    for warning in warnings {
+                        eprintln!("{} {}", warning_label(), warning);
+                    }
+                    for attachment in attachments {
+                        println!("{}", t!("repl.attachment_added", attachment.path.display()));
+                        session_manager.pending_attachments.push(attachment);
+                    }
+                    println!("{}", t!("repl.attachments_pending"));
+                }
+            }
+        }
+
+        "context" => {
+            let Some(session_id) = session_manager.current_session_id.clone() else {
+                return Ok(false);
+            };
+            match (parts.get(1).copied(), parts.get(2).copied()) {
+                (Some("add"), Some(dir)) => {
+                    let root = match canonical_root(dir) {
+                        Ok(root) => root,
+                        Err(e) => {
+                            eprintln!("{} {}", error_label(), e);
+                            return Ok(false);
+                        }
+                    };
+                    let session = session_manager.get_session_mut(&session_id)?;
+                    if !session.pinned_contexts.contains(&root) {
+                        session.pinned_contexts.push(root.clone());
+                    }
+                    let count = session.pinned_contexts.len();
+                    let budget = session_manager.current_model().context_window() / 2 / count;
+                    match session_manager.rendered_context(&root, budget) {
+                        Ok(rendered) => println!(
+                            "{}",
+                            t!(
+                                "repl.context_pinned",
+                                root.display(),
+                                rendered.included_files,
+                                rendered.total_files,
+                                estimate_tokens(&rendered.content)
+                            )
+                        ),
+                        Err(e) => eprintln!("{} {}", error_label(), e),
+                    }
+                }
+                (Some("remove"), Some(target)) => {
+                    let session = session_manager.get_session_mut(&session_id)?;
+                    let index = target
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| i.checked_sub(1))
+                        .or_else(|| {
+                            let root = canonical_root(target).ok()?;
+                            session.pinned_contexts.iter().position(|p| *p == root)
+                        });
+                    match index.filter(|i| *i < session.pinned_contexts.len()) {
+                        Some(i) => {
+                            let root = session.pinned_contexts.remove(i);
+                            println!("{}", t!("repl.context_removed", root.display()));
+                        }
+                        None => eprintln!("{} {}", error_label(), t!("repl.context_not_found", target)),
+                    }
+                }
+                (Some("clear"), _) => {
+                    session_manager.get_session_mut(&session_id)?.pinned_contexts.clear();
+                    println!("{}", t!("repl.context_cleared"));
+                }
+                (None, _) | (Some("list"), _) => {
+                    let session = session_manager.get_session_mut(&session_id)?;
+                    if session.pinned_contexts.is_empty() {
+                        println!("{}", t!("repl.context_empty"));
+                    }
+                    for (i, root) in session.pinned_contexts.iter().enumerate() {
+                        println!("{}. {}", i + 1, root.display());
+                    }
+                }
+                _ => println!("{}", t!("repl.context_usage")),
+            }
+        }
+
+        "rag" => {
+            let Some(session_id) = session_manager.current_session_id.clone() else {
+                return Ok(false);
+            };
+            match parts.get(1).copied() {
+                Some("on") if parts.len() > 2 => {
+                    let root = match canonical_root(parts[2]) {
+                        Ok(root) => root,
+                        Err(e) => {
+                            eprintln!("{} {}", error_label(), e);
+                            return Ok(false);
+                        }
+                    };
+                    if !Bm25Index::path_for(&session_manager.indexes_path, &root).exists() {
+                        println!("{}", t!("repl.rag_no_index", root.display()));
+                    }
+                    let top_k = parts
+                        .get(3)
+                        .and_then(|k| k.parse().ok())
+                        .unwrap_or(DEFAULT_TOP_K);
+                    println!("{}", t!("repl.rag_on", root.display(), top_k));
+                    session_manager.get_session_mut(&session_id)?.retrieval =
+                        Some(RetrievalSettings { root, top_k });
+                }
+                Some("off") => {
+                    session_manager.get_session_mut(&session_id)?.retrieval = None;
+                    println!("{}", t!("repl.rag_off"));
+                }
+                None => match &session_manager.get_session_mut(&session_id)?.retrieval {
+                    Some(settings) => println!(
+                        "{}",
+                        t!("repl.rag_status", settings.root.display(), settings.top_k)
+                    ),
+                    None => println!("{}", t!("repl.rag_disabled")),
+                },
+                _ => println!("{}", t!("repl.rag_usage")),
+            }
+        }
+
+        "t" | "template" => {
+            let library = TemplateLibrary::new(session_manager.templates_path.clone());
+            let Some(name) = parts.get(1).copied() else {
+                match library.list() {
+                    Ok(names) => {
+                        println!("{}", t!("repl.templates", library.dir.display()));
+                        for name in names {
+                            println!("  {}", name);
+                        }
+                    }
+                    Err(e) => eprintln!("{} {}", error_label(), e),
+                }
+                return Ok(false);
+            };
+
+            let template = match library.load(name) {
+                Ok(template) => template,
+                Err(e) => {
+                    eprintln!("{} {}", error_label(), e);
+                    return Ok(false);
+                }
+            };
+
+            let args = command[parts[0].len()..].trim_start()[name.len()..].trim();
+            let mut vars = parse_assignments(args);
+            for variable in variables(&template) {
+                if vars.contains_key(&variable) {
+                    continue;
+                }
+                match Text::new(&format!("{}:", variable)).prompt() {
+                    Ok(value) => {
+                        vars.insert(variable, value);
+                    }
+                    Err(_) => {
+                        println!("{}", t!("common.cancelled"));
+                        return Ok(false);
+                    }
+                }
+            }
+
+            let content = match render(&template, &vars) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("{} {:#}", error_label(), e);
+                    return Ok(false);
+                }
+            };
+
+            if let Some(session) = session_manager.get_current_session() {
+                session.add_message("user", &content);
+            }
+            println!("{}", t!("repl.template_sent", name, content.chars().count()));
+            if let Err(e) = generate_response(session_manager).await {
+                eprintln!("{} {}", error_label(), e);
+            }
+        }
+
+        "persona" => {
+            if let Some(name) = parts.get(1) {
+                match session_manager.set_persona(name) {
+                    Ok(_) => println!("{}", t!("repl.persona_switched", name)),
+                    Err(e) => eprintln!("{} {}", error_label(), e),
+                }
+            } else {
+                let current = session_manager
+                    .get_current_session()
+                    .and_then(|s| s.persona.clone())
+                    .unwrap_or_else(|| t!("common.none").to_string());
+                println!("{}", t!("persona.current", current));
+                let names: Vec<&str> = session_manager
+                    .config
+                    .personas
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect();
+                println!("{}", t!("repl.personas", names.join(", ")));
+            }
+        }
+
+        "model" => match parts.get(1).copied() {
+            Some("default") => {
+                session_manager.set_session_model(None)?;
+                println!("{}", t!("repl.model_default", session_manager.current_model().display_name()));
+            }
+            Some(name) => match session_manager.set_session_model(Some(name)) {
+                Ok(_) => println!("{}", t!("repl.model_switched", name)),
+                Err(e) => eprintln!("{} {}", error_label(), e),
+            },
+            None => {
+                let current = session_manager.current_model().display_name().to_string();
+                println!("{}", t!("repl.model_current", current));
+                for model in session_manager.config.all_models() {
+                    let marker = if model.display_name() == current { "*" } else { " " };
+                    match &model.description {
+                        Some(description) => {
+                            println!(" {} {} - {}", marker, model.display_name(), description)
+                        }
+                        None => println!(" {} {}", marker, model.display_name()),
+                    }
+                }
+            }
+        },
+
+        "raw" => {
+            session_manager.raw_output = !session_manager.raw_output;
+            if session_manager.raw_output {
+                println!("{}", t!("repl.raw_on"));
+            } else {
+                println!("{}", t!("repl.raw_off"));
+            }
+        }
+
+        "search" if parts.len() > 1 => {
+            if let Err(e) = run_search(session_manager, command["search".len()..].trim(), 20, OutputFormat::Table) {
+                eprintln!("{} {}", error_label(), e);
             }
         }
 
         "config" => {
-            println!("当前配置:");
-            println!("  最大会话数: {}", session_manager.config.max_sessions);
-            println!("  自动保存: {}", session_manager.config.auto_save);
-            println!("  默认模型: {:?}", session_manager.config.default_model);
-            println!("  主题: {:?}", session_manager.config.theme);
+            let config = &session_manager.config;
+            println!("{}", t!("config.title"));
+            println!("{}", t!("config.max_sessions", config.max_sessions));
+            println!("{}", t!("config.auto_save", config.auto_save));
+            println!("{}", t!("config.default_model", config.default_model.display_name()));
+            println!("{}", t!("config.api_key", config.default_model.api_key_label()));
+            println!("{}", t!("config.theme", config.theme_label()));
+            println!("{}", t!("config.language", language_label(config.language)));
         }
 
         "help" => {
@@ -359,7 +1571,7 @@ pub fn handle_command(
         }
 
         _ => {
-            println!("未知命令: {}", command);
+            println!("{}", t!("repl.unknown_command", command));
             print_help();
         }
     }
@@ -367,15 +1579,468 @@ pub fn handle_command(
     Ok(false)
 }
 
+// REPL 支持的命令名，用于 Tab 补全
+pub const REPL_COMMANDS: &[&str] = &[
+    "exit", "list", "switch", "new", "generate", "save", "rename", "title", "history", "fork",
+    "branches", "checkout", "last", "retry", "edit", "undo", "alternatives", "tag", "pin",
+    "unpin", "folder", "file", "context", "rag", "t", "template", "persona", "model", "raw",
+    "search", "config", "help",
+];
+
 fn print_help() {
-    println!("\n可用命令:");
-    println!("  /exit             - 退出");
-    println!("  /list             - 列出所有会话");
-    println!("  /switch <ID>      - 切换到指定会话");
-    println!("  /new [标题]       - 创建新会话");
-    println!("  /save             - 手动保存会话");
-    println!("  /rename <新标题>  - 重命名当前会话");
-    println!("  /title            - 显示当前会话标题");
-    println!("  /config           - 显示当前配置");
-    println!("  /help             - 显示帮助");
+    println!("{}", t!("repl.help"));
+}
+
+// 用编辑后的内容替换提问：在原提问的位置开启新分支并重新请求回复
+async fn resend_edited(
+    session_manager: &mut SessionManager,
+    message_id: &str,
+    edited: &str,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(session) = session_manager.get_current_session() {
+        session.rewind_before(message_id)?;
+        session.add_message("user", edited);
+    }
+    if let Err(e) = generate_response(session_manager).await {
+        eprintln!("{} {}", error_label(), e);
+    }
+    Ok(())
+}
+
+
+// 单次提问，除回复外的所有输出都写到标准错误；请求失败时以非零状态退出，
+// 没有输入时以状态 2 退出
+async fn run_ask(
+    session_manager: &mut SessionManager,
+    sessions_path: &PathBuf,
+    prompt: &str,
+    session: Option<&str>,
+    no_save: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut input = String::new();
+    if !io::stdin().is_terminal() {
+        io::stdin().read_to_string(&mut input)?;
+    }
+    let content = ask_content(prompt, &input)
+        .ok_or_else(|| CommandError::new("empty_input", t!("ask.empty")).with_exit_code(2))?;
+
+    // 单次提问不改变交互模式下的当前会话
+    let previous = (session_manager.current_session_id.clone(), session_manager.recent_sessions.clone());
+    match session {
+        Some(query) => {
+            let id = session_manager.resolve_session(query)?;
+            session_manager.switch_session(&id)?;
+        }
+        // 临时会话带上标题，不再请求模型生成
+        None if no_save => {
+            session_manager.open_ephemeral_session(t!("session.default_title"));
+        }
+        None => {
+            session_manager.create_session(None);
+        }
+    }
+    if let Some(session) = session_manager.get_current_session() {
+        session.add_message("user", &content);
+    }
+
+    let mut renderer = StreamRenderer::new(session_manager.raw_output);
+    let result = generate_response_with(session_manager, |delta| renderer.push(delta)).await;
+    let citations = result
+        .and_then(|citations| Ok(renderer.finish().map(|_| citations)?))
+        .map_err(|e| CommandError::new("request_failed", format!("{:#}", e)))?;
+    if !citations.is_empty() {
+        eprintln!("{}", notice(t!("reply.citations", citations.join(", "))));
+    }
+
+    if !no_save {
+        // 之前的当前会话可能已被保留策略归档
+        let (current, recent) = previous;
+        session_manager.current_session_id = current.filter(|id| session_manager.sessions.contains_key(id));
+        session_manager.recent_sessions = recent.into_iter().filter(|id| session_manager.sessions.contains_key(id)).collect();
+        session_manager.save_sessions(sessions_path)?;
+    }
+    Ok(())
+}
+
+// 提示词之后附加标准输入的内容，两者都为空时返回 None
+fn ask_content(prompt: &str, input: &str) -> Option<String> {
+    match (prompt.trim(), input.trim()) {
+        ("", "") => None,
+        (prompt, "") => Some(prompt.to_string()),
+        ("", input) => Some(input.to_string()),
+        (prompt, input) => Some(format!("{}\n\n{}", prompt, input)),
+    }
+}
+
+// 默认隐藏配置中的 key，指定 --reveal 时原样显示
+fn displayed_config(config: &Config, reveal: bool) -> Config {
+    if reveal { config.clone() } else { config.redacted() }
+}
+
+fn archive_reason_label(reason: ArchiveReason) -> &'static str {
+    match reason {
+        ArchiveReason::Retention => t!("archive.retention"),
+        ArchiveReason::Deleted => t!("archive.deleted"),
+    }
+}
+
+// 配置中未指定语言时显示实际使用的语言及其来源
+fn language_label(language: Option<Locale>) -> String {
+    match (language, i18n::locale_env_source()) {
+        (Some(locale), _) => locale.to_string(),
+        (None, Some(name)) => t!("config.language_auto", i18n::locale(), name),
+        (None, None) => t!("config.language_default", i18n::locale()),
+    }
+}
+
+// 按字符截断，避免在多字节字符中间切分
+fn preview(content: &str, max_chars: usize) -> String {
+    let content = content.replace('\n', " ");
+    if content.chars().count() > max_chars {
+        format!("{}...", content.chars().take(max_chars).collect::<String>())
+    } else {
+        content
+    }
+}
+
+fn run_search(
+    session_manager: &SessionManager,
+    input: &str,
+    limit: usize,
+    output: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let hits = SearchQuery::parse(input)
+        .and_then(|query| search(session_manager, &query, limit))
+        .map_err(|e| CommandError::new("search_failed", format!("{:#}", e)))?;
+    match hits {
+        hits if output == OutputFormat::Json => print_json(&hits)?,
+        hits if output == OutputFormat::Plain => {
+            for hit in &hits {
+                print_plain(&[
+                    hit.session_id.clone(),
+                    hit.role.clone(),
+                    hit.timestamp.to_rfc3339(),
+                    hit.snippet.clone(),
+                ]);
+            }
+        }
+        hits if hits.is_empty() => println!("{}", t!("search.no_results")),
+        hits => {
+            for (i, hit) in hits.iter().enumerate() {
+                println!(
+                    "{}. {} [ID: {}] {} {}",
+                    i + 1,
+                    hit.session_title,
+                    &hit.session_id[..8],
+                    hit.role,
+                    hit.timestamp.format("%Y-%m-%d %H:%M")
+                );
+                println!("   {}", hit.highlighted);
+            }
+        }
+    }
+    Ok(())
+}
+
+// 启动时检查配置文件：无法解析时列出错误并询问是否修复，不修复则退出；
+// 其他问题返回给调用方，在主题初始化后显示
+fn check_config_file(config_path: &Path) -> Result<Vec<Issue>, Box<dyn Error>> {
+    let Ok(content) = fs::read_to_string(config_path) else {
+        return Ok(Vec::new());
+    };
+    let (config, issues) = check_config(&content);
+    if config.is_some() {
+        return Ok(issues);
+    }
+
+    // 颜色来自配置中的主题，此时还无法使用
+    eprintln!("{}", t!("check.failed", config_path.display()));
+    for issue in &issues {
+        eprintln!("{} {}", t!("label.error"), issue);
+    }
+    if !offer_repair(config_path, &content)? {
+        eprintln!("{}", t!("check.repair_hint"));
+        process::exit(1);
+    }
+    let (_, issues) = check_config(&fs::read_to_string(config_path)?);
+    Ok(issues)
+}
+
+fn run_config_check(config_path: &Path, output: OutputFormat) -> Result<(), Box<dyn Error>> {
+    let content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(_) => {
+            report(output, &t!("check.missing", config_path.display()), json!([]))?;
+            return Ok(());
+        }
+    };
+    let (_, issues) = check_config(&content);
+    match output {
+        OutputFormat::Json => print_json(&issues)?,
+        OutputFormat::Plain => {
+            for issue in &issues {
+                print_plain(&[
+                    to_value(&issue.severity)?.as_str().unwrap_or_default(),
+                    &issue.field,
+                    &issue.line.map(|line| line.to_string()).unwrap_or_default(),
+                    &issue.message,
+                ]);
+            }
+        }
+        OutputFormat::Table => {
+            if issues.is_empty() {
+                println!("{}", t!("check.passed"));
+            }
+            for issue in &issues {
+                println!("{} {}", severity_label(issue.severity), issue);
+            }
+        }
+    }
+
+    if !issues.iter().any(|issue| issue.severity == Severity::Error) {
+        return Ok(());
+    }
+    // 只有交互使用时才询问是否修复
+    if output != OutputFormat::Table || !offer_repair(config_path, &content)? {
+        process::exit(1);
+    }
+    let (_, remaining) = check_config(&fs::read_to_string(config_path)?);
+    let mut failed = false;
+    for issue in remaining.iter().filter(|issue| issue.severity == Severity::Error) {
+        eprintln!("{} {}", error_label(), issue);
+        failed = true;
+    }
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+// 询问是否用默认值修复配置，修复前把原文件备份为 config.json.bak；不是交互使用时不修复
+fn offer_repair(config_path: &Path, content: &str) -> Result<bool, Box<dyn Error>> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    let yes = t!("common.yes");
+    let choice = Select::new(t!("check.repair_prompt"), vec![yes, t!("common.no")]).prompt();
+    if !matches!(choice, Ok(choice) if choice == yes) {
+        return Ok(false);
+    }
+    let backup = config_path.with_extension("json.bak");
+    fs::write(&backup, content)?;
+    let config = repair_config(content);
+    fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
+    eprintln!("{}", t!("check.repaired", backup.display()));
+    Ok(true)
+}
+
+// 会请求模型接口（包括生成标题）的命令
+fn calls_api(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Start { .. } | Commands::Tui | Commands::Ask { .. } | Commands::Resume | Commands::Restore { .. }
+    )
+}
+
+// 修改带来新的错误时拒绝保存，新的警告只提示
+fn checked_change(before: &Config, after: Config) -> Result<Config, CommandError> {
+    let issues = check_change(before, &after);
+    let errors: Vec<String> = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .map(|issue| issue.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(CommandError::new("invalid_config", errors.join("\n")));
+    }
+    for issue in &issues {
+        eprintln!("{} {}", severity_label(issue.severity), issue);
+    }
+    Ok(after)
+}
+
+fn severity_label(severity: Severity) -> StyledContent<&'static str> {
+    match severity {
+        Severity::Error => error_label(),
+        Severity::Warning => warning_label(),
+    }
+}
+
+// 配置项的显示形式：字符串原样显示，其他值显示为 JSON
+fn value_label(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// 会话在未过滤列表中的序号（从 1 开始），与 #序号 对应
+fn session_index(session_manager: &SessionManager, session_id: &str) -> usize {
+    session_manager
+        .list_sessions()
+        .iter()
+        .position(|s| s.id == session_id)
+        .map_or(0, |i| i + 1)
+}
+
+fn print_summaries(output: OutputFormat, summaries: &[SessionSummary]) -> serde_json::Result<()> {
+    match output {
+        OutputFormat::Json => print_json(summaries)?,
+        OutputFormat::Plain | OutputFormat::Table => {
+            for summary in summaries {
+                print_plain(&summary.plain_row());
+            }
+        }
+    }
+    Ok(())
+}
+
+// 列表中显示的置顶、标签和目录
+fn session_labels(session: &Session) -> String {
+    let mut labels = String::new();
+    if session.pinned {
+        labels.push_str(t!("session.pinned_label"));
+    }
+    for tag in &session.tags {
+        labels.push_str(&format!(" #{}", tag));
+    }
+    if let Some(folder) = &session.folder {
+        labels.push_str(&t!("session.folder_label", folder));
+    }
+    labels
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::manager::testing::{temp_manager, TempDir};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 本地模拟接口：流式请求返回一段回复，标题请求返回固定标题
+    async fn mock_api(reply: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buffer).await.unwrap_or(0);
+                    request.extend_from_slice(&buffer[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    let complete = text.split_once("\r\n\r\n").is_some_and(|(headers, body)| {
+                        let length = headers
+                            .to_ascii_lowercase()
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:")?.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        body.len() >= length
+                    });
+                    if n == 0 || complete {
+                        break;
+                    }
+                }
+                let (content_type, body) = if String::from_utf8_lossy(&request).contains("\"stream\":true") {
+                    let chunk = json!({ "choices": [{ "delta": { "content": reply } }] });
+                    ("text/event-stream", format!("data: {}\n\ndata: [DONE]\n\n", chunk))
+                } else {
+                    let completion = json!({ "choices": [{ "message": { "role": "assistant", "content": "标题" } }] });
+                    ("application/json", completion.to_string())
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content_type,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}/chat", address)
+    }
+
+    // 当前会话中已有一轮对话；with_system 为 false 时第一条提问是根消息
+    async fn repl(name: &str, with_system: bool) -> (SessionManager, TempDir, PathBuf) {
+        let (mut manager, dir) = temp_manager(name);
+        manager.config.default_model.api_url = mock_api("a2").await;
+        manager.config.default_model.api_key = "k".to_string();
+        manager.create_session(None);
+        let session = manager.get_current_session().unwrap();
+        if !with_system {
+            let system = session.messages[0].id.clone();
+            session.rewind_before(&system).unwrap();
+        }
+        session.add_message("user", "q1");
+        session.add_message("assistant", "a1");
+        let sessions_path = dir.0.join("sessions.json");
+        (manager, dir, sessions_path)
+    }
+
+    fn branch(manager: &mut SessionManager) -> Vec<String> {
+        let session = manager.get_current_session().unwrap();
+        session
+            .active_branch()
+            .iter()
+            .filter(|m| m.role != "system")
+            .map(|m| m.content.clone())
+            .collect()
+    }
+
+    fn versions(manager: &mut SessionManager, content: &str) -> usize {
+        let session = manager.get_current_session().unwrap();
+        let message = session.messages.iter().find(|m| m.content == content).unwrap();
+        session.alternatives(&message.id).len()
+    }
+
+    #[tokio::test]
+    async fn undo_first_exchange_empties_the_branch() {
+        for with_system in [true, false] {
+            let (mut manager, _dir, sessions_path) = repl("repl-undo", with_system).await;
+            handle_command("undo", &mut manager, &sessions_path).await.unwrap();
+            assert!(branch(&mut manager).is_empty());
+
+            let session = manager.get_current_session().unwrap();
+            session.add_message("user", "q2");
+            assert_eq!(branch(&mut manager), ["q2"]);
+            assert_eq!(versions(&mut manager, "q1"), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_first_reply_adds_a_sibling_reply() {
+        for with_system in [true, false] {
+            let (mut manager, _dir, sessions_path) = repl("repl-retry", with_system).await;
+            handle_command("retry", &mut manager, &sessions_path).await.unwrap();
+            assert_eq!(branch(&mut manager), ["q1", "a2"]);
+            assert_eq!(versions(&mut manager, "a1"), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn edit_first_question_starts_a_sibling_branch() {
+        for with_system in [true, false] {
+            let (mut manager, _dir, _) = repl("repl-edit", with_system).await;
+            let session = manager.get_current_session().unwrap();
+            let first = session.last_on_branch("user").unwrap().id.clone();
+            resend_edited(&mut manager, &first, "q1 edited").await.unwrap();
+            assert_eq!(branch(&mut manager), ["q1 edited", "a2"]);
+            assert_eq!(versions(&mut manager, "q1"), 2);
+        }
+    }
+
+    #[test]
+    fn ask_content_joins_prompt_and_stdin() {
+        assert_eq!(ask_content("  ", "\n"), None);
+        assert_eq!(ask_content(" 解释 ", ""), Some("解释".to_string()));
+        assert_eq!(ask_content("", " fn main() {}\n"), Some("fn main() {}".to_string()));
+        assert_eq!(ask_content("解释", "fn main() {}\n"), Some("解释\n\nfn main() {}".to_string()));
+    }
+
+    #[test]
+    fn empty_input_exits_with_status_two() {
+        let error: Box<dyn Error> = CommandError::new("empty_input", t!("ask.empty")).with_exit_code(2).into();
+        assert_eq!(exit_code(error.as_ref()), 2);
+        let error: Box<dyn Error> = "boom".into();
+        assert_eq!(exit_code(error.as_ref()), 1);
+    }
 }
@@ -0,0 +1,36 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::process::Command;
+use uuid::Uuid;
+
+use crate::i18n::t;
+
+// 在 $EDITOR 中编辑文本，返回保存后的内容
+pub fn edit_text(initial: &str, extension: &str) -> io::Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    let path = env::temp_dir().join(format!("mobius-{}.{}", Uuid::new_v4(), extension));
+    fs::write(&path, initial)?;
+
+    // EDITOR 可能带参数，例如 "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path),
+        Ok(status) => Err(io::Error::other(t!("editor.exit_status", status))),
+        Err(e) => Err(e),
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
@@ -0,0 +1,64 @@
+use clap::Command;
+
+use crate::i18n::{lookup, t};
+
+// 用消息目录填充命令行帮助：子命令说明的键为 cmd.<路径>，参数说明先查
+// arg.<路径>.<参数>，再查通用的 arg.<参数>
+pub fn localize(command: Command) -> Command {
+    let mut command = localize_at(command.about(t!("cmd.mobius")), "");
+    // help 子命令在 build 时才生成
+    command.build();
+    command.mut_subcommand("help", |help| help.about(t!("cmd.help")))
+}
+
+fn localize_at(mut command: Command, path: &str) -> Command {
+    let template = format!("{{about-with-newline}}\n{} {{usage}}\n\n{{all-args}}{{after-help}}", t!("clap.usage"));
+    command = command
+        .help_template(template)
+        .subcommand_help_heading(t!("clap.commands"))
+        .disable_help_flag(true)
+        .arg(
+            clap::Arg::new("help")
+                .short('h')
+                .long("help")
+                .action(clap::ArgAction::Help)
+                .help(t!("arg.help")),
+        );
+    if path.is_empty() {
+        command = command.disable_version_flag(true).arg(
+            clap::Arg::new("version")
+                .short('V')
+                .long("version")
+                .action(clap::ArgAction::Version)
+                .help(t!("arg.version")),
+        );
+    }
+
+    let ids: Vec<String> = command.get_arguments().map(|a| a.get_id().to_string()).collect();
+    for id in ids {
+        let help = lookup(&format!("arg.{}.{}", path, id))
+            .filter(|_| !path.is_empty())
+            .or_else(|| lookup(&format!("arg.{}", id)));
+        command = command.mut_arg(id, |arg| {
+            let heading = if arg.is_positional() { t!("clap.arguments") } else { t!("clap.options") };
+            let arg = arg.help_heading(heading);
+            match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            }
+        });
+    }
+
+    let names: Vec<String> = command.get_subcommands().map(|c| c.get_name().to_string()).collect();
+    for name in names {
+        let child = if path.is_empty() { name.clone() } else { format!("{}.{}", path, name) };
+        command = command.mut_subcommand(name, |sub| {
+            let sub = match lookup(&format!("cmd.{}", child)) {
+                Some(about) => sub.about(about),
+                None => sub,
+            };
+            localize_at(sub, &child)
+        });
+    }
+    command
+}
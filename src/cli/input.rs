@@ -0,0 +1,235 @@
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::error::ReadlineError;
+use crossterm::style::Stylize;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{
+    Cmd, CompletionType, Config, Context, EditMode, Editor, Helper, KeyCode, KeyEvent, Modifiers,
+};
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::cli::alter::REPL_COMMANDS;
+use crate::i18n::t;
+use crate::session::manager::SessionManager;
+use crate::session::theme::{palette, stdout_styled, warning_label};
+
+// 多行输入块的分隔符，单独一行 """ 开始和结束
+const BLOCK_DELIMITER: &str = "\"\"\"";
+
+// Tab 补全：命令名、/switch 的会话、/file 的路径、/model 和 /persona 的名称
+struct ReplHelper {
+    // (会话ID, 标题)
+    sessions: Vec<(String, String)>,
+    models: Vec<String>,
+    personas: Vec<String>,
+    files: FilenameCompleter,
+}
+
+impl ReplHelper {
+    fn names(candidates: &[String], prefix: &str) -> Vec<Pair> {
+        candidates
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: name.clone(),
+            })
+            .collect()
+    }
+
+    // 按ID前缀或标题匹配会话，补全为完整ID
+    fn sessions(&self, prefix: &str) -> Vec<Pair> {
+        let needle = prefix.to_lowercase();
+        self.sessions
+            .iter()
+            .filter(|(id, title)| id.starts_with(prefix) || title.to_lowercase().contains(&needle))
+            .map(|(id, title)| Pair {
+                display: format!("{}  {}", &id[..8.min(id.len())], title),
+                replacement: id.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let Some(command) = line[..pos].strip_prefix('/') else {
+            return Ok((pos, Vec::new()));
+        };
+
+        let Some((name, arg)) = command.split_once(' ') else {
+            let commands = REPL_COMMANDS
+                .iter()
+                .filter(|c| c.starts_with(command))
+                .map(|c| Pair {
+                    display: c.to_string(),
+                    replacement: format!("{} ", c),
+                })
+                .collect();
+            return Ok((1, commands));
+        };
+
+        let start = pos - arg.len();
+        match name {
+            "switch" => Ok((start, self.sessions(arg))),
+            "model" => Ok((start, Self::names(&self.models, arg))),
+            "persona" => Ok((start, Self::names(&self.personas, arg))),
+            "file" => self.files.complete(line, pos, ctx),
+            _ => Ok((pos, Vec::new())),
+        }
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+// 提示符和用户输入按主题着色
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if !stdout_styled() {
+            return Cow::Borrowed(line);
+        }
+        Cow::Owned(format!("{}", line.with(palette().user)))
+    }
+
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(&'s self, prompt: &'p str, _default: bool) -> Cow<'b, str> {
+        if !stdout_styled() {
+            return Cow::Borrowed(prompt);
+        }
+        Cow::Owned(format!("{}", prompt.with(palette().prompt).bold()))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, kind: CmdKind) -> bool {
+        kind != CmdKind::MoveCursor
+    }
+}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+// REPL 的行编辑器：emacs 快捷键、持久化历史、Ctrl-R 反向搜索、括号粘贴
+pub struct LineReader {
+    editor: Editor<ReplHelper, FileHistory>,
+    history_path: PathBuf,
+}
+
+impl LineReader {
+    pub fn new(history_path: PathBuf) -> rustyline::Result<Self> {
+        let config = Config::builder()
+            .edit_mode(EditMode::Emacs)
+            .history_ignore_dups(true)?
+            .history_ignore_space(true)
+            .max_history_size(1000)?
+            .bracketed_paste(true)
+            .completion_type(CompletionType::List)
+            .build();
+
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(ReplHelper {
+            sessions: Vec::new(),
+            models: Vec::new(),
+            personas: Vec::new(),
+            files: FilenameCompleter::new(),
+        }));
+        // Alt-Enter 插入换行而不发送
+        editor.bind_sequence(KeyEvent(KeyCode::Enter, Modifiers::ALT), Cmd::Newline);
+
+        if history_path.exists() {
+            editor.load_history(&history_path)?;
+        }
+
+        Ok(LineReader {
+            editor,
+            history_path,
+        })
+    }
+
+    // 每次读取前刷新补全候选，会话和配置可能已被命令修改
+    pub fn refresh_completions(&mut self, session_manager: &SessionManager) {
+        let Some(helper) = self.editor.helper_mut() else {
+            return;
+        };
+        helper.sessions = session_manager
+            .list_sessions()
+            .into_iter()
+            .map(|s| (s.id.clone(), s.title.clone()))
+            .collect();
+        helper.models = session_manager
+            .config
+            .all_models()
+            .map(|m| m.display_name().to_string())
+            .collect();
+        helper.personas = session_manager
+            .config
+            .personas
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+    }
+
+    // 读取一条完整的输入，Ctrl-D 时返回 None
+    pub fn read_input(&mut self, prompt: &str) -> rustyline::Result<Option<String>> {
+        loop {
+            let line = match self.editor.readline(prompt) {
+                Ok(line) => line,
+                // Ctrl-C 放弃当前输入
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+
+            let input = match line.trim_start().strip_prefix(BLOCK_DELIMITER) {
+                Some(rest) => match rest.trim_end().strip_suffix(BLOCK_DELIMITER) {
+                    Some(inner) => inner.to_string(),
+                    None => self.read_block(rest)?,
+                },
+                None => line,
+            };
+
+            if !input.trim().is_empty() {
+                self.editor.add_history_entry(input.as_str())?;
+                if let Err(e) = self.editor.append_history(&self.history_path) {
+                    eprintln!("{} {}", warning_label(), t!("input.history_failed", e));
+                }
+            }
+            return Ok(Some(input));
+        }
+    }
+
+    // 读取到下一个 """ 为止的多行内容
+    fn read_block(&mut self, first_line: &str) -> rustyline::Result<String> {
+        let mut lines = Vec::new();
+        if !first_line.trim().is_empty() {
+            lines.push(first_line.to_string());
+        }
+        loop {
+            match self.editor.readline("... ") {
+                Ok(line) => match line.trim_end().strip_suffix(BLOCK_DELIMITER) {
+                    Some(last) => {
+                        if !last.trim().is_empty() {
+                            lines.push(last.to_string());
+                        }
+                        break;
+                    }
+                    None => lines.push(line),
+                },
+                Err(ReadlineError::Eof) => break,
+                Err(ReadlineError::Interrupted) => return Ok(String::new()),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
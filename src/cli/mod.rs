@@ -1,2 +1,6 @@
 pub mod alter;
-pub mod actions;
\ No newline at end of file
+pub mod actions;
+pub mod editor;
+pub mod help;
+pub mod input;
+pub mod output;
\ No newline at end of file
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt;
+
+use crate::session::archive::{ArchiveReason, ArchivedSession};
+use crate::session::manager::{Session, SessionError};
+use crate::session::theme::error_label;
+
+// 命令结果的输出格式：table 为默认的可读文本；plain 每行一条记录，字段以制表符分隔；
+// json 的字段名与 Session、Config 保持一致
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Plain,
+    Json,
+}
+
+// 列表中的一条会话，不包含消息内容
+#[derive(Debug, Serialize)]
+pub struct SessionSummary<'a> {
+    // 可用 #序号 引用会话；回收站中为回收站列表的序号
+    pub index: usize,
+    pub id: &'a str,
+    pub title: &'a str,
+    pub created_at: DateTime<Utc>,
+    pub last_accessed: DateTime<Utc>,
+    pub message_count: usize,
+    pub pinned: bool,
+    pub tags: &'a [String],
+    pub folder: Option<&'a str>,
+    pub persona: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<ArchiveReason>,
+}
+
+impl<'a> SessionSummary<'a> {
+    pub fn new(index: usize, session: &'a Session, current: bool) -> Self {
+        SessionSummary {
+            index,
+            id: &session.id,
+            title: &session.title,
+            created_at: session.created_at,
+            last_accessed: session.last_accessed,
+            message_count: session.messages.len(),
+            pinned: session.pinned,
+            tags: &session.tags,
+            folder: session.folder.as_deref(),
+            persona: session.persona.as_deref(),
+            model: session.model.as_deref(),
+            current,
+            archived_at: None,
+            reason: None,
+        }
+    }
+
+    pub fn archived(index: usize, archived: &'a ArchivedSession) -> Self {
+        SessionSummary {
+            archived_at: Some(archived.archived_at),
+            reason: Some(archived.reason),
+            ..SessionSummary::new(index, &archived.session, false)
+        }
+    }
+
+    // plain 格式的字段顺序：序号、ID、标题、最后访问、消息数量、置顶、标签、目录、归档原因
+    pub fn plain_row(&self) -> Vec<String> {
+        vec![
+            self.index.to_string(),
+            self.id.to_string(),
+            self.title.to_string(),
+            self.last_accessed.to_rfc3339(),
+            self.message_count.to_string(),
+            self.pinned.to_string(),
+            self.tags.join(","),
+            self.folder.unwrap_or_default().to_string(),
+            self.reason.map(|r| format!("{:?}", r)).unwrap_or_default(),
+        ]
+    }
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+// 经过文本转换，f32 字段保持原来的写法
+pub fn to_value<T: Serialize>(value: &T) -> serde_json::Result<Value> {
+    serde_json::from_str(&serde_json::to_string(value)?)
+}
+
+// 字段中的制表符和换行替换为空格，保证一行一条记录
+pub fn print_plain<S: AsRef<str>>(row: &[S]) {
+    let fields: Vec<String> = row
+        .iter()
+        .map(|field| field.as_ref().replace(['\t', '\n', '\r'], " "))
+        .collect();
+    println!("{}", fields.join("\t"));
+}
+
+// 操作结果：json 格式输出 value，plain 格式把 value 展开为 路径、值 两列，table 格式输出提示文本
+pub fn report(format: OutputFormat, message: &str, value: Value) -> serde_json::Result<()> {
+    match format {
+        OutputFormat::Json => print_json(&value),
+        OutputFormat::Plain => {
+            for (key, value) in flatten(&value) {
+                print_plain(&[key, value]);
+            }
+            Ok(())
+        }
+        OutputFormat::Table => {
+            println!("{}", message);
+            Ok(())
+        }
+    }
+}
+
+// 命令执行失败，code 不随语言变化，供脚本判断失败原因
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: &'static str,
+    pub message: String,
+    // 进程退出状态，默认为 1
+    pub exit_code: i32,
+}
+
+impl CommandError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        CommandError {
+            code,
+            message: message.into(),
+            exit_code: 1,
+        }
+    }
+
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CommandError {}
+
+fn error_code(error: &(dyn Error + 'static)) -> &'static str {
+    if let Some(error) = error.downcast_ref::<CommandError>() {
+        error.code
+    } else if let Some(error) = error.downcast_ref::<SessionError>() {
+        error.code()
+    } else {
+        "error"
+    }
+}
+
+// 输出命令的错误：json 格式向标准输出写 {"error": {"code", "message"}}；
+// plain 格式向标准错误写 error、code、message 三个字段；table 格式显示提示文本
+// 命令失败时的退出状态
+pub fn exit_code(error: &(dyn Error + 'static)) -> i32 {
+    error.downcast_ref::<CommandError>().map_or(1, |error| error.exit_code)
+}
+
+pub fn print_error(format: OutputFormat, error: &(dyn Error + 'static)) {
+    let code = error_code(error);
+    let message = format!("{:#}", error);
+    match format {
+        OutputFormat::Json => {
+            let value = json!({ "error": { "code": code, "message": message } });
+            println!("{}", serde_json::to_string_pretty(&value).unwrap_or_default());
+        }
+        OutputFormat::Plain => {
+            let fields: Vec<String> = ["error", code, &message]
+                .iter()
+                .map(|field| field.replace(['\t', '\n', '\r'], " "))
+                .collect();
+            eprintln!("{}", fields.join("\t"));
+        }
+        OutputFormat::Table => eprintln!("{} {}", error_label(), message),
+    }
+}
+
+// 把 JSON 展开为 点号路径 -> 值，数组元素以序号作为路径的一段
+pub fn flatten(value: &Value) -> Vec<(String, String)> {
+    let mut rows = Vec::new();
+    flatten_into(value, String::new(), &mut rows);
+    rows
+}
+
+fn flatten_into(value: &Value, path: String, rows: &mut Vec<(String, String)>) {
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                flatten_into(value, join(key), rows);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, value) in items.iter().enumerate() {
+                flatten_into(value, join(&i.to_string()), rows);
+            }
+        }
+        Value::String(text) => rows.push((path, text.clone())),
+        other => rows.push((path, other.to_string())),
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use globset::Glob;
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+
+// 单个文件和单条消息附件的大小上限
+pub const MAX_FILE_BYTES: u64 = 256 * 1024;
+pub const MAX_TOTAL_BYTES: u64 = 512 * 1024;
+
+// 除 .gitignore 外额外支持的忽略文件
+pub const IGNORE_FILE_NAME: &str = ".mobiusignore";
+
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+// 内容中有 ``` 时使用更长的围栏
+pub fn fence_for(content: &str) -> String {
+    let mut fence = "```".to_string();
+    while content.contains(&fence) {
+        fence.push('`');
+    }
+    fence
+}
+
+impl Attachment {
+    // 以文件名和语言标注的代码块
+    pub fn to_fenced(&self) -> String {
+        let fence = fence_for(&self.content);
+        format!(
+            "`{}`\n{}{}\n{}\n{}",
+            self.path.display(),
+            fence,
+            language_for(&self.path),
+            self.content.trim_end(),
+            fence
+        )
+    }
+}
+
+// 遵循 .gitignore 和 .mobiusignore 的目录遍历
+pub fn walker(root: &Path) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        // 不在 git 仓库中时也读取 .gitignore
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME);
+    builder
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+// 展开路径：普通文件直接使用，目录和通配符会遍历并跳过被忽略的文件
+pub fn expand_path(pattern: &str) -> Result<Vec<PathBuf>> {
+    if !is_glob(pattern) {
+        let path = PathBuf::from(pattern);
+        if path.is_file() {
+            return Ok(vec![path]);
+        }
+        if path.is_dir() {
+            return Ok(walk_files(&path));
+        }
+        anyhow::bail!(t!("files.not_found", pattern));
+    }
+
+    // 通配符之前的部分作为遍历起点
+    let base: PathBuf = Path::new(pattern)
+        .components()
+        .take_while(|c| !is_glob(&c.as_os_str().to_string_lossy()))
+        .collect();
+    let base = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+
+    let matcher = Glob::new(pattern.trim_start_matches("./"))
+        .with_context(|| t!("files.invalid_glob", pattern))?
+        .compile_matcher();
+    let mut files: Vec<PathBuf> = walk_files(&base)
+        .into_iter()
+        .filter(|path| matcher.is_match(path.strip_prefix("./").unwrap_or(path)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+pub fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = walker(root)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+// 包含 NUL 字节或不是 UTF-8 的文件视为二进制文件
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(8000)];
+    head.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+// 读取文本文件，跳过二进制和超过大小限制的文件
+pub fn read_text_file(path: &Path, max_bytes: u64) -> Result<String> {
+    let size = fs::metadata(path)
+        .with_context(|| t!("files.read_failed", path.display()))?
+        .len();
+    if size > max_bytes {
+        anyhow::bail!(t!("files.too_large", size / 1024, path.display()));
+    }
+    let bytes = fs::read(path).with_context(|| t!("files.read_failed", path.display()))?;
+    if is_binary(&bytes) {
+        anyhow::bail!(t!("files.binary", path.display()));
+    }
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+// 读取所有路径对应的文件，返回附件和警告信息
+pub fn collect_attachments(patterns: &[String]) -> (Vec<Attachment>, Vec<String>) {
+    let mut attachments: Vec<Attachment> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut total: u64 = 0;
+
+    for pattern in patterns {
+        let paths = match expand_path(pattern) {
+            Ok(paths) if paths.is_empty() => {
+                warnings.push(t!("files.no_match", pattern));
+                continue;
+            }
+            Ok(paths) => paths,
+            Err(e) => {
+                warnings.push(e.to_string());
+                continue;
+            }
+        };
+
+        for path in paths {
+            if attachments.iter().any(|a| a.path == path) {
+                continue;
+            }
+            match read_text_file(&path, MAX_FILE_BYTES) {
+                Ok(content) => {
+                    total += content.len() as u64;
+                    if total > MAX_TOTAL_BYTES {
+                        warnings.push(t!("files.total_too_large", MAX_TOTAL_BYTES / 1024));
+                        return (attachments, warnings);
+                    }
+                    attachments.push(Attachment { path, content });
+                }
+                Err(e) => warnings.push(e.to_string()),
+            }
+        }
+    }
+    (attachments, warnings)
+}
+
+// 提取输入中的 @路径，只有路径存在或是通配符时才视为附件
+pub fn extract_mentions(input: &str) -> (String, Vec<String>) {
+    let mut mentions = Vec::new();
+    let text: Vec<String> = input
+        .split(' ')
+        .map(|word| match word.strip_prefix('@') {
+            Some(path)
+                if !path.is_empty() && (is_glob(path) || Path::new(path).exists()) =>
+            {
+                mentions.push(path.to_string());
+                path.to_string()
+            }
+            _ => word.to_string(),
+        })
+        .collect();
+    (text.join(" "), mentions)
+}
+
+// 用户消息加上附件代码块
+pub fn compose_message(text: &str, attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        return text.to_string();
+    }
+    let blocks: Vec<String> = attachments.iter().map(Attachment::to_fenced).collect();
+    format!("{}\n\n{}", text, blocks.join("\n\n"))
+}
+
+pub fn language_for(path: &Path) -> &'static str {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_lowercase());
+    if file_name.as_deref() == Some("dockerfile") {
+        return "dockerfile";
+    }
+    if file_name.as_deref() == Some("makefile") {
+        return "makefile";
+    }
+
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "ts" | "mts" => "typescript",
+        "tsx" => "tsx",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "lua" => "lua",
+        "sh" | "bash" | "zsh" => "bash",
+        "ps1" => "powershell",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "vue" => "vue",
+        "xml" => "xml",
+        "json" => "json",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "md" | "markdown" => "markdown",
+        _ => "",
+    }
+}
@@ -0,0 +1,3 @@
+pub mod files;
+pub mod project;
+pub mod rag;
\ No newline at end of file
@@ -0,0 +1,193 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::context::files::{read_text_file, walk_files, Attachment, MAX_FILE_BYTES};
+use crate::i18n::t;
+use crate::search::index::fnv1a;
+use crate::search::tokenizer::is_cjk;
+
+// 文件结构最多列出的条目数
+const MAX_TREE_ENTRIES: usize = 400;
+
+// 优先放入上下文的项目说明和清单文件
+const PRIORITY_FILES: &[&str] = &[
+    "readme.md",
+    "readme",
+    "cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "makefile",
+    "dockerfile",
+];
+
+// 粗略估算 token 数：中日韩文字约每字一个，其余约每四个字符一个
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut cjk = 0;
+    let mut other: usize = 0;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+// 已渲染的项目上下文，文件没有变化时复用
+#[derive(Debug, Clone)]
+pub struct RenderedContext {
+    pub fingerprint: u64,
+    pub budget: usize,
+    pub content: String,
+    pub included_files: usize,
+    pub total_files: usize,
+}
+
+// 根据文件路径、大小和修改时间计算指纹，用于判断是否需要刷新；
+// 指纹保存在检索索引文件中，跨版本需要保持稳定
+pub fn fingerprint(files: &[PathBuf]) -> u64 {
+    let mut parts: Vec<Vec<u8>> = Vec::new();
+    for path in files {
+        parts.push(path.as_os_str().as_encoded_bytes().to_vec());
+        if let Ok(metadata) = path.metadata() {
+            parts.push(metadata.len().to_le_bytes().to_vec());
+            if let Ok(modified) = metadata.modified()
+                && let Ok(duration) = modified.duration_since(UNIX_EPOCH)
+            {
+                parts.push(duration.as_millis().to_le_bytes().to_vec());
+            }
+        }
+    }
+    fnv1a(parts)
+}
+
+// 生成文件结构和在预算内尽量多的文件内容
+pub fn render_context(root: &Path, budget: usize) -> Result<RenderedContext> {
+    if !root.is_dir() {
+        anyhow::bail!(t!("project.dir_missing", root.display()));
+    }
+    let files = walk_files(root);
+    let relative: Vec<PathBuf> = files
+        .iter()
+        .map(|p| p.strip_prefix(root).unwrap_or(p).to_path_buf())
+        .collect();
+
+    let tree = render_tree(&relative);
+    let mut used = estimate_tokens(&tree);
+
+    // 说明和清单文件优先，其次是层级浅、体积小的文件
+    let mut ordered: Vec<(usize, &PathBuf)> = files.iter().enumerate().collect();
+    ordered.sort_by_key(|(i, path)| {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let priority = if PRIORITY_FILES.contains(&name.as_str()) { 0 } else { 1 };
+        let size = path.metadata().map(|m| m.len()).unwrap_or(u64::MAX);
+        (priority, relative[*i].components().count(), size)
+    });
+
+    let mut blocks = Vec::new();
+    for (i, path) in ordered {
+        if used >= budget {
+            break;
+        }
+        let Ok(content) = read_text_file(path, MAX_FILE_BYTES) else {
+            continue;
+        };
+        let block = Attachment {
+            path: relative[i].clone(),
+            content,
+        }
+        .to_fenced();
+        let tokens = estimate_tokens(&block);
+        if used + tokens > budget {
+            continue;
+        }
+        used += tokens;
+        blocks.push(block);
+    }
+
+    let content = t!(
+        "project.context_header",
+        root.display(),
+        blocks.len(),
+        files.len(),
+        tree,
+        blocks.join("\n\n")
+    );
+
+    Ok(RenderedContext {
+        fingerprint: fingerprint(&files),
+        budget,
+        content,
+        included_files: blocks.len(),
+        total_files: files.len(),
+    })
+}
+
+// 缩进形式的目录树，目录以 / 结尾
+fn render_tree(files: &[PathBuf]) -> String {
+    let mut lines = Vec::new();
+    let mut previous: Vec<String> = Vec::new();
+
+    for path in files.iter().take(MAX_TREE_ENTRIES) {
+        let components: Vec<String> = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let (file, dirs) = components.split_last().expect("path has components");
+
+        let common = dirs
+            .iter()
+            .zip(previous.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, dir) in dirs.iter().enumerate().skip(common) {
+            lines.push(format!("{}{}/", "  ".repeat(depth), dir));
+        }
+        lines.push(format!("{}{}", "  ".repeat(dirs.len()), file));
+        previous = dirs.to_vec();
+    }
+    if files.len() > MAX_TREE_ENTRIES {
+        lines.push(t!("project.tree_more", files.len() - MAX_TREE_ENTRIES));
+    }
+    lines.join("\n")
+}
+
+pub fn canonical_root(dir: &str) -> Result<PathBuf> {
+    let path = Path::new(dir)
+        .canonicalize()
+        .with_context(|| t!("project.dir_missing", dir))?;
+    if !path.is_dir() {
+        anyhow::bail!(t!("project.not_dir", dir));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_stable_across_runs() {
+        // 固定值：更换工具链后已保存的索引不会被误判为过期
+        assert_eq!(fingerprint(&[PathBuf::from("/nonexistent/a.rs")]), 0x8a7321d049cdf503);
+
+        let dir = std::env::temp_dir().join(format!("mobius-test-fingerprint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.rs");
+        std::fs::write(&file, "fn a() {}").unwrap();
+        let before = fingerprint(std::slice::from_ref(&file));
+        assert_eq!(fingerprint(std::slice::from_ref(&file)), before);
+        std::fs::write(&file, "fn a() { b() }").unwrap();
+        let after = fingerprint(std::slice::from_ref(&file));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(after, before);
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::context::files::{fence_for, language_for, read_text_file, walk_files};
+use crate::context::project::fingerprint;
+use crate::i18n::t;
+use crate::search::tokenizer::{index_terms, query_terms};
+
+// 每个片段的行数，以及相邻片段重叠的行数
+const CHUNK_LINES: usize = 40;
+const CHUNK_OVERLAP: usize = 8;
+// 参与索引的单个文件大小上限
+const MAX_INDEX_FILE_BYTES: u64 = 1024 * 1024;
+
+// BM25 参数
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+// 会话的检索设置：每条用户消息从该项目的索引中检索 top_k 个片段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrievalSettings {
+    pub root: PathBuf,
+    pub top_k: usize,
+}
+
+pub const DEFAULT_TOP_K: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chunk {
+    // 相对于索引根目录的路径
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+    term_freqs: HashMap<String, u32>,
+    length: u32,
+}
+
+impl Chunk {
+    pub fn citation(&self) -> String {
+        format!("{}:{}-{}", self.path.display(), self.start_line, self.end_line)
+    }
+}
+
+// 项目的 BM25 索引，保存在数据目录的 indexes/ 下
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bm25Index {
+    pub root: PathBuf,
+    pub built_at: DateTime<Utc>,
+    pub fingerprint: u64,
+    pub file_count: usize,
+    pub chunks: Vec<Chunk>,
+    doc_freqs: HashMap<String, u32>,
+    avg_length: f64,
+}
+
+// 代码标识符额外按下划线和驼峰拆分，例如 get_current_session、SessionManager
+fn code_terms(text: &str, query: bool) -> Vec<String> {
+    let mut terms = if query { query_terms(text) } else { index_terms(text) };
+    let mut parts = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        if !word.is_ascii() {
+            continue;
+        }
+        let mut current = String::new();
+        let mut previous_lower = false;
+        for c in word.chars() {
+            let boundary = c == '_' || (c.is_uppercase() && previous_lower);
+            if boundary && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            if c != '_' {
+                current.extend(c.to_lowercase());
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+        if !current.is_empty() && current.len() < word.len() {
+            parts.push(current);
+        }
+    }
+    for part in parts {
+        if !terms.contains(&part) {
+            terms.push(part);
+        }
+    }
+    terms
+}
+
+fn chunk_file(path: &Path, relative: &Path) -> Vec<Chunk> {
+    let Ok(content) = read_text_file(path, MAX_INDEX_FILE_BYTES) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..end].join("\n");
+        if !text.trim().is_empty() {
+            let mut term_freqs: HashMap<String, u32> = HashMap::new();
+            // 路径也参与匹配，方便按文件名检索
+            let terms = code_terms(&format!("{} {}", relative.display(), text), false);
+            let length = terms.len() as u32;
+            for term in terms {
+                *term_freqs.entry(term).or_default() += 1;
+            }
+            chunks.push(Chunk {
+                path: relative.to_path_buf(),
+                start_line: start + 1,
+                end_line: end,
+                text,
+                term_freqs,
+                length,
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+impl Bm25Index {
+    pub fn build(root: &Path) -> Self {
+        let files = walk_files(root);
+        let mut chunks = Vec::new();
+        for path in &files {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            chunks.extend(chunk_file(path, relative));
+        }
+
+        let mut doc_freqs: HashMap<String, u32> = HashMap::new();
+        for chunk in &chunks {
+            for term in chunk.term_freqs.keys() {
+                *doc_freqs.entry(term.clone()).or_default() += 1;
+            }
+        }
+        let total: u64 = chunks.iter().map(|c| c.length as u64).sum();
+        let avg_length = if chunks.is_empty() {
+            0.0
+        } else {
+            total as f64 / chunks.len() as f64
+        };
+
+        Bm25Index {
+            root: root.to_path_buf(),
+            built_at: Utc::now(),
+            fingerprint: fingerprint(&files),
+            file_count: files.len(),
+            chunks,
+            doc_freqs,
+            avg_length,
+        }
+    }
+
+    // 每个项目一个索引文件，文件名取根目录路径的 FNV-1a 哈希，跨版本保持稳定
+    pub fn path_for(indexes_dir: &Path, root: &Path) -> PathBuf {
+        let hash = root
+            .to_string_lossy()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        indexes_dir.join(format!("{:016x}.json", hash))
+    }
+
+    pub fn load(indexes_dir: &Path, root: &Path) -> Result<Self> {
+        let path = Self::path_for(indexes_dir, root);
+        let index_file = File::open(&path)
+            .with_context(|| t!("rag.no_index", root.display()))?;
+        Ok(serde_json::from_reader(index_file)?)
+    }
+
+    pub fn save(&self, indexes_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(indexes_dir)?;
+        let path = Self::path_for(indexes_dir, &self.root);
+        let index_file = File::create(&path)?;
+        serde_json::to_writer(index_file, self)?;
+        Ok(path)
+    }
+
+    // 索引建立后文件是否发生过变化
+    pub fn is_stale(&self) -> bool {
+        fingerprint(&walk_files(&self.root)) != self.fingerprint
+    }
+
+    pub fn search(&self, query: &str, top_k: usize) -> Vec<(&Chunk, f64)> {
+        let terms = code_terms(query, true);
+        let total = self.chunks.len() as f64;
+
+        let mut scored: Vec<(&Chunk, f64)> = self
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let length_norm = 1.0 - B + B * chunk.length as f64 / self.avg_length.max(1.0);
+                let score: f64 = terms
+                    .iter()
+                    .filter_map(|term| {
+                        let tf = *chunk.term_freqs.get(term)? as f64;
+                        let df = *self.doc_freqs.get(term)? as f64;
+                        let idf = (1.0 + (total - df + 0.5) / (df + 0.5)).ln();
+                        Some(idf * tf * (K1 + 1.0) / (tf + K1 * length_norm))
+                    })
+                    .sum();
+                (chunk, score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+// 注入给模型的检索结果
+pub fn render_chunks(chunks: &[&Chunk]) -> String {
+    let blocks: Vec<String> = chunks
+        .iter()
+        .map(|chunk| {
+            let fence = fence_for(&chunk.text);
+            format!(
+                "`{}`\n{}{}\n{}\n{}",
+                chunk.citation(),
+                fence,
+                language_for(&chunk.path),
+                chunk.text,
+                fence
+            )
+        })
+        .collect();
+    t!("rag.chunks_header", blocks.join("\n\n"))
+}
@@ -0,0 +1,414 @@
+// English text; keys missing here fall back to zh-CN
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Common
+    ("label.error", "Error:"),
+    ("label.warning", "Warning:"),
+    ("common.yes", "Yes"),
+    ("common.no", "No"),
+    ("common.none", "(none)"),
+    ("common.default", "(default)"),
+    ("common.current", " (current)"),
+    ("common.cancelled", "Cancelled"),
+    ("session.default_title", "New session"),
+    ("session.pinned_label", " [pinned]"),
+    ("session.folder_label", " folder:{}"),
+    // SessionError
+    ("error.io", "I/O error: {}"),
+    ("error.json", "JSON error: {}"),
+    ("error.session_not_found", "Session not found: {}"),
+    ("error.message_not_found", "Message not found: {}"),
+    ("error.persona_not_found", "Persona not found: {}"),
+    ("error.model_not_found", "Model not found: {}"),
+    ("error.ambiguous_session", "'{}' matches more than one session:"),
+    ("error.invalid_session_id", "Invalid session ID"),
+    ("error.no_current_session", "No current session"),
+    // SessionManager
+    ("manager.context_failed", "Cannot build project context {}: {}"),
+    ("manager.index_stale", "Files changed since the index was built; run mobius index {} to rebuild"),
+    ("manager.search_index_failed", "Cannot update the search index: {}"),
+    ("manager.sessions_missing", "Session file does not exist"),
+    // Command line
+    ("cli.load_sessions_failed", "Cannot load sessions: {}"),
+    ("cli.load_state_failed", "Cannot load session state: {}"),
+    ("cli.session_restored", "Restored session: {}"),
+    ("cli.session_created", "Created new session: {}"),
+    ("cli.session_continued", "Continuing last session: {}"),
+    ("cli.session_switched", "Switched to session: {}"),
+    ("cli.resuming", "Resuming last session: {}"),
+    ("cli.nothing_to_resume", "No previous session to resume"),
+    ("cli.creating_session", "Creating a new session..."),
+    ("cli.generate_confirm", "Generate from this conversation?: {}"),
+    ("persona.current", "Current persona: {}"),
+    ("persona.list_entry", "{}{} model: {} temperature: {} top_p: {}"),
+    ("persona.default_marker", " (default)"),
+    ("persona.exists", "Persona already exists: {}"),
+    ("persona.added", "Added persona: {}"),
+    ("persona.updated", "Updated persona: {}"),
+    ("persona.removed", "Removed persona: {}"),
+    ("persona.default_set", "Default persona set to: {}"),
+    ("persona.default_prompt", "You are a helpful programming assistant. Answer accurately and concisely."),
+    ("persona.reviewer_prompt", "You are a senior code reviewer. Point out defects, potential risks and possible improvements in the code, and give concrete suggestions for changes."),
+    ("title.prompt", "Write a short title of at most six words for the conversation below, in the language the conversation uses. Output only the title."),
+    ("config.title", "Current configuration:"),
+    ("config.max_sessions", "  Max sessions: {}"),
+    ("config.max_age", "  Max age (days): {}"),
+    ("config.max_size", "  Total size limit (MB): {}"),
+    ("config.auto_save", "  Auto save: {}"),
+    ("config.default_model", "  Default model: {}"),
+    ("config.api_key", "  API key: {}"),
+    ("config.theme", "  Theme: {}"),
+    ("config.language", "  Language: {}"),
+    ("config.language_auto", "{} (from {})"),
+    ("config.language_default", "{} (default)"),
+    ("config.max_sessions_set", "Max sessions set to: {}"),
+    ("config.max_age_set", "Max session age set to: {} days"),
+    ("config.max_size_set", "Total session size limit set to: {} MB"),
+    ("config.model_set", "Default model set to: {}"),
+    ("config.unknown_theme", "Unknown theme {}; use light, dark, system or a name from custom_themes"),
+    ("config.theme_set", "Theme set to: {}"),
+    ("config.auto_save_enabled", "Auto save enabled"),
+    ("config.auto_save_disabled", "Auto save disabled"),
+    ("config.unknown_language", "Unknown language {}; use zh-CN, en or auto"),
+    ("config.language_set", "Language set to: {}"),
+    ("config.language_cleared", "Language will follow LANG and related environment variables"),
+    ("config.unknown_key", "Unknown config key: {}"),
+    ("config.invalid_value", "Invalid value for {}: {}"),
+    ("config.key_env_missing", "Environment variable {} is not set"),
+    ("config.key_file_failed", "Failed to read key file {}"),
+    ("config.key_cmd_failed", "Failed to run key command {}"),
+    ("config.key_cmd_status", "Key command {} failed: {}"),
+    ("config.value_set", "{} set to: {}"),
+    ("config.value_unset", "{} reset, now: {}"),
+    ("config.edit_again", "Edit again?"),
+    ("config.saved", "Configuration saved"),
+    ("config.model_entry", "{}{}  model: {}  url: {}"),
+    ("config.model_default", " (default model)"),
+    ("config.model_exists", "Model already exists: {}"),
+    ("config.model_added", "Added model: {}"),
+    ("config.model_removed", "Removed model: {}"),
+    ("config.model_is_default", "{} is the default model and cannot be removed"),
+    ("check.line", "line {}"),
+    ("check.failed", "Config file {} has errors:"),
+    ("check.passed", "Configuration OK"),
+    ("check.missing", "Config file {} does not exist; defaults will be used"),
+    ("check.repair_prompt", "Fill in or replace the broken fields with defaults?"),
+    ("check.repair_hint", "Run mobius config check to check and repair the configuration"),
+    ("check.repaired", "Configuration repaired; the original was backed up to {}"),
+    ("check.invalid_url", "Invalid URL: {}"),
+    ("check.unsupported_scheme", "Unsupported scheme {}; expected http or https"),
+    ("check.empty_api_key", "api_key is empty; requests will be rejected"),
+    ("check.key_env_unset", "environment variable {} is not set; requests will fail"),
+    ("check.key_file_missing", "key file {} does not exist; requests will fail"),
+    ("check.empty_model", "Model name is empty"),
+    ("check.duplicate_model", "Duplicate model name: {}"),
+    ("check.unknown_model", "Model is not configured: {}"),
+    ("check.unknown_persona", "Persona does not exist: {}"),
+    ("check.unknown_theme", "No theme {} in custom_themes"),
+    ("check.out_of_range", "{} is outside the range {} to {}"),
+    ("check.zero_max_sessions", "Max sessions is 0; every new session will clean up old ones"),
+    ("session.renamed", "Session '{}' renamed to '{}'"),
+    ("session.trashed", "Session '{}' moved to trash"),
+    ("session.pinned", "Session '{}' pinned"),
+    ("session.unpinned", "Session '{}' unpinned"),
+    ("session.folder", "Folder of session '{}': {}"),
+    ("session.tags", "Tags of session '{}': {}"),
+    ("session.restored", "Session '{}' restored"),
+    ("cleanup.plan", "{} session(s) would be cleaned up:"),
+    ("cleanup.entry", "  {} [ID: {}] last accessed: {}"),
+    ("cleanup.done", "Archived {} session(s), {} remaining"),
+    ("list.empty", "No sessions"),
+    ("list.header", "Sessions:"),
+    ("list.header_all", "All sessions:"),
+    ("list.created_at", "  Created: {}"),
+    ("list.last_accessed", "  Last accessed: {}"),
+    ("list.message_count", "  Messages: {}"),
+    ("list.archived_header", "Archived sessions:"),
+    ("archive.retention", "archived by retention policy"),
+    ("archive.deleted", "deleted"),
+    ("tag.empty", "No tags"),
+    ("index.building", "Building index: {}"),
+    ("index.done", "Indexed {} file(s), {} chunk(s), saved to {}"),
+    ("trash.empty", "Trash is empty"),
+    ("trash.entry", "{}. {} [ID: {}] {} at {}, messages: {}"),
+    ("trash.confirm_empty", "Permanently delete all sessions in the trash?"),
+    ("trash.emptied", "Permanently deleted {} session(s)"),
+    ("export.all", "Exported {} session(s) to {}"),
+    ("export.session", "Exported session '{}' to {}"),
+    ("export.current", "Exported current session to {}"),
+    ("import.done", "Imported sessions from {}"),
+    ("search.no_results", "No matching messages"),
+    // Chat loop
+    ("loop.unknown_session", "Unknown session"),
+    ("loop.header", "Session: {} [ID: {}]"),
+    ("loop.help_hint", "Type /help to list commands"),
+    ("loop.attachment", "Attachment: {}"),
+    ("reply.citations", "Sources: {}"),
+    ("input.history_failed", "Cannot save input history: {}"),
+    ("editor.exit_status", "Editor exited with: {}"),
+    // REPL commands
+    ("repl.exit", "Exited, sessions saved"),
+    ("repl.list_header", "\n--- Sessions ---"),
+    ("repl.current_session", "Current session: {} [ID: {}]"),
+    ("repl.generated", "Generated files from conversation: {}"),
+    ("repl.saved", "Sessions saved"),
+    ("repl.renamed", "Current session renamed to: {}"),
+    ("repl.title", "Current session title: {}"),
+    ("repl.invalid_index", "Invalid message number: {}"),
+    ("repl.forked", "Forked at message {}; the next message starts a new branch"),
+    ("repl.branch_entry", "{}. {} message(s), last question: {}{}"),
+    ("repl.checked_out", "Switched to branch {}"),
+    ("repl.branch_not_found", "Branch not found: {}"),
+    ("repl.no_reply", "No reply on the current branch yet"),
+    ("repl.nothing_to_retry", "Nothing to retry"),
+    ("repl.nothing_to_edit", "No message to edit"),
+    ("repl.editor_failed", "Cannot open editor: {}"),
+    ("repl.unchanged", "Message unchanged"),
+    ("repl.undone", "Undid the last exchange; see /alternatives or /branches"),
+    ("repl.nothing_to_undo", "Nothing to undo"),
+    ("repl.message_not_found", "Message not found"),
+    ("repl.versions", "{} version(s):"),
+    ("repl.version_header", "\n--- Version {}{} ---"),
+    ("repl.tag_usage", "Usage: /tag [add|remove <tags...>]"),
+    ("repl.tags", "Current session tags: {}"),
+    ("repl.pinned", "Current session pinned"),
+    ("repl.unpinned", "Current session unpinned"),
+    ("repl.folder", "Current session folder: {}"),
+    ("repl.no_attachments", "No pending attachments"),
+    ("repl.attachment_entry", "  {} ({} bytes)"),
+    ("repl.attachments_cleared", "Cleared pending attachments"),
+    ("repl.attachment_added", "Attached: {}"),
+    ("repl.attachments_pending", "Attachments will be sent with the next message"),
+    ("repl.context_pinned", "Pinned project context: {} ({}/{} files, about {} tokens)"),
+    ("repl.context_removed", "Removed project context: {}"),
+    ("repl.context_not_found", "Project context not found: {}"),
+    ("repl.context_cleared", "Cleared project contexts"),
+    ("repl.context_empty", "No project context pinned to this session"),
+    ("repl.context_usage", "Usage: /context [list|add <dir>|remove <number>|clear]"),
+    ("repl.rag_no_index", "Hint: no index yet, run mobius index {} first"),
+    ("repl.rag_on", "Retrieval enabled: {} ({} chunks per message)"),
+    ("repl.rag_off", "Retrieval disabled"),
+    ("repl.rag_status", "Retrieval enabled: {} ({} chunks per message)"),
+    ("repl.rag_disabled", "Retrieval is off"),
+    ("repl.rag_usage", "Usage: /rag [on <dir> [chunks]|off]"),
+    ("repl.templates", "Templates ({}):"),
+    ("repl.template_sent", "Sent template {} ({} characters)"),
+    ("repl.persona_switched", "Switched to persona: {}"),
+    ("repl.personas", "Personas: {}"),
+    ("repl.model_default", "Back to the default model: {}"),
+    ("repl.model_switched", "Current session now uses model: {}"),
+    ("repl.model_current", "Current model: {}"),
+    ("repl.raw_on", "Replies will be printed raw"),
+    ("repl.raw_off", "Replies will be rendered as markdown"),
+    ("repl.unknown_command", "Unknown command: {}"),
+    (
+        "repl.help",
+        "
+Commands:
+  /exit             - Quit
+  /list             - List sessions
+  /switch <ID>      - Switch session by ID prefix, #number or title
+  /switch -         - Switch back to the previous session
+  /new [title]      - Create a session
+  /save             - Save sessions now
+  /rename <title>   - Rename the current session
+  /title            - Show the current session title
+  /history          - Show messages on the current branch
+  /fork [number]    - Branch from a message
+  /branches         - List branches of the current session
+  /checkout <number> - Switch to a branch
+  /last             - Re-render the last reply at the current terminal width
+  /retry            - Regenerate the last reply
+  /edit             - Edit the last message and regenerate
+  /undo             - Undo the last exchange
+  /alternatives [number] - Show other versions of a message
+  /tag [add|remove <tags...>] - Show or change tags of the current session
+  /pin, /unpin      - Pin or unpin the current session
+  /folder [folder]  - Show or set the current session folder
+  /file <paths...>  - Attach files to the next message, globs allowed; /file clear to reset
+  @path             - Attach a file inline in a message
+  /context add <dir> - Pin a project context sent with every message
+  /context [list|remove <number>|clear] - Manage project contexts
+  /rag [on <dir> [chunks]|off] - Retrieve related code from the project index
+  /t [template] [var=value...] - List templates or render and send one
+  /persona [name]   - Show or switch the persona of the current session
+  /model [name|default] - Show or switch the model of the current session
+  /search <query>   - Search all sessions; supports \"phrase\" role:user after:/before:YYYY-MM-DD
+  /raw              - Toggle raw output and markdown rendering
+  /config           - Show configuration
+  /help             - Show this help
+
+Input:
+  Alt-Enter         - Insert a newline without sending
+  \"\"\"               - Start and end a multi-line message on its own line
+  Ctrl-R            - Search input history
+  Ctrl-C / Ctrl-D   - Clear input / quit",
+    ),
+    // Files and commands from code blocks
+    ("generate.confirm_files", "Generate the files?"),
+    ("generate.file_failed", "Failed to generate file: {}"),
+    ("generate.not_selected", "Nothing selected"),
+    ("generate.confirm_commands", "Found {} command(s), run them?"),
+    ("generate.command_error", "Error running command: {}"),
+    ("generate.confirm_command", "Run command {}?"),
+    ("generate.command_failed", "Command failed: {}"),
+    ("generate.spawn_failed", "Failed to execute command: {}"),
+    ("generate.not_executed", "Command not executed"),
+    ("generate.missing_path", "code block missing path metadata"),
+    ("generate.file_done", "File generated at: {}"),
+    ("parser.start", "Parsing content..."),
+    ("parser.done", "Parsing finished, generated: {}"),
+    // Attachments, project context, templates and search
+    ("files.not_found", "File does not exist: {}"),
+    ("files.invalid_glob", "Invalid glob: {}"),
+    ("files.read_failed", "Cannot read file: {}"),
+    ("files.too_large", "File too large ({} KB): {}"),
+    ("files.binary", "Skipping binary file: {}"),
+    ("files.no_match", "No files match: {}"),
+    ("files.total_too_large", "Attachments exceed {} KB in total; remaining files ignored"),
+    ("project.dir_missing", "Directory does not exist: {}"),
+    ("project.not_dir", "Not a directory: {}"),
+    ("rag.no_index", "No index yet, run mobius index {} first"),
+    ("project.context_header", "Below is the context of project {} (contents of {}/{} files included).\n\nFile tree:\n{}\n\n{}"),
+    ("project.tree_more", "... {} more files"),
+    ("rag.chunks_header", "Below are code snippets retrieved from the project that are relevant to the question. Cite them as file:line when you use them.\n\n{}"),
+    ("template.not_found", "Template not found: {}"),
+    ("template.unclosed", "Unclosed {{ in template"),
+    ("template.spawn_failed", "Cannot run command: {}"),
+    ("template.command_failed", "Command failed: {}\n{}"),
+    ("template.missing_variable", "Missing template variable: {}"),
+    ("template.default_review", "Review the code below and point out defects, potential risks and improvements:\n\nFile: {{path}}\n\n```\n{{file:{{path}}}}\n```\n"),
+    ("template.default_review_diff", "Review the following changes, point out problems and suggest fixes:\n\n```diff\n{{shell:git diff}}\n```\n"),
+    ("template.default_translate", "Translate the text below into {{language}}, keeping its formatting:\n\n{{text}}\n"),
+    ("template.default_tests", "Write unit tests for the code below, covering normal paths and edge cases:\n\n```\n{{file:{{path}}}}\n```\n"),
+    ("search.empty_query", "Empty search query"),
+    ("search.invalid_date", "Invalid date: {} (expected YYYY-MM-DD)"),
+    ("search.index_failed", "Cannot update the search index"),
+    ("theme.invalid_color", "Invalid color in theme {}: {}"),
+    ("ask.empty", "No prompt; pass it as an argument or through stdin"),
+    // Full-screen UI
+    ("tui.you", "You"),
+    ("tui.assistant", "Assistant"),
+    ("tui.sessions", " Sessions ({})"),
+    ("tui.input_title", " Message (Enter to send, Alt-Enter for newline) "),
+    ("tui.rename_title", " Rename session (Enter to confirm, Esc to cancel) "),
+    ("tui.streaming_title", " Generating (Esc to cancel) "),
+    ("tui.persona", "| persona: {} "),
+    ("tui.context", "| context≈{} tokens "),
+    ("tui.usage", "| last: {} in {} out "),
+    ("tui.confirm_delete", "Delete session {}? (y/n)"),
+    ("tui.sidebar_hints", "↑↓ select · Enter open · n new · r rename · d delete · Tab back"),
+    ("tui.hints", "Tab sessions · Ctrl-N new · F2 rename · PgUp/PgDn scroll · Ctrl-Q quit"),
+    ("tui.delete_cancelled", "Delete cancelled"),
+    ("tui.session_created", "Created new session: {}"),
+    ("tui.renamed", "Renamed to: {}"),
+    ("tui.trashed", "Session moved to trash"),
+    ("tui.attached", "Attached {} file(s)"),
+    ("tui.generation_cancelled", "Generation cancelled"),
+    // Command line help
+    ("clap.usage", "Usage:"),
+    ("clap.commands", "Commands"),
+    ("clap.options", "Options"),
+    ("clap.arguments", "Arguments"),
+    ("arg.help", "Print help"),
+    ("arg.version", "Print version"),
+    ("arg.raw", "Print replies raw instead of rendering markdown"),
+    ("arg.output", "Output format for command results: table for reading, plain for tab-separated records, json for scripts"),
+    ("arg.session_id", "Session ID, ID prefix, #number or title"),
+    ("arg.name", "Persona name"),
+    ("arg.prompt", "System prompt"),
+    ("arg.model", "Model to use (name or model from Config.models)"),
+    ("arg.temperature", "Sampling temperature"),
+    ("arg.top_p", "Nucleus sampling probability"),
+    ("arg.tags", "Tags"),
+    ("cmd.mobius", "A chat session manager"),
+    ("cmd.help", "Print this message or the help of the given subcommand(s)"),
+    ("cmd.start", "Start chatting, continuing the last session by default"),
+    ("arg.start.title", "Create a new session with this title instead of continuing the last one"),
+    ("arg.start.restore", "Restore a session (ID prefix, #number or title)"),
+    ("arg.start.persona", "Persona for the current session"),
+    ("cmd.tui", "Full-screen interface"),
+    ("cmd.ask", "Ask once: stdin is appended to the prompt and only the answer is written to stdout"),
+    ("arg.ask.prompt", "Prompt; may be omitted to use stdin only"),
+    ("arg.ask.session", "Continue this session (ID prefix, #index or title)"),
+    ("arg.ask.no_save", "Use a temporary session that is not saved"),
+    ("cmd.persona", "Manage personas"),
+    ("cmd.persona.list", "List personas"),
+    ("cmd.persona.add", "Add a persona; opens the editor without --prompt"),
+    ("cmd.persona.edit", "Edit a persona; opens the editor for the system prompt without options"),
+    ("cmd.persona.remove", "Remove a persona"),
+    ("cmd.persona.set-default", "Set the persona for new sessions"),
+    ("cmd.config", "Show and change configuration"),
+    ("cmd.config.show", "Show configuration"),
+    ("cmd.config.set-max-sessions", "Set the maximum number of sessions"),
+    ("cmd.config.set-max-age", "Set the maximum session age in days, 0 for no limit"),
+    ("cmd.config.set-max-size", "Set the total session size limit in MB, 0 for no limit"),
+    ("cmd.config.set-model", "Set the default model name"),
+    ("cmd.config.set-theme", "Set the theme: light, dark, system or a name from custom_themes"),
+    ("cmd.config.toggle-auto-save", "Enable or disable auto save"),
+    ("cmd.config.set-language", "Set the interface language: zh-CN, en, or auto to follow LANG"),
+    ("cmd.config.get", "Read a config value by dotted path, e.g. default_model.api_url"),
+    ("cmd.config.set", "Change a config value; the value is parsed as JSON, or taken as a string if that fails"),
+    ("cmd.config.unset", "Remove a config value; required values are reset to their defaults"),
+    ("cmd.config.edit", "Edit the config file in $EDITOR and check it on save"),
+    ("cmd.config.check", "Check the config file and optionally repair errors with defaults"),
+    ("cmd.config.model", "Manage the models in Config.models"),
+    ("cmd.config.model.list", "List all models"),
+    ("cmd.config.model.add", "Add a model; --api-url and --api-key default to those of the default model"),
+    ("cmd.config.model.remove", "Remove a model"),
+    ("arg.config.get.key", "Dotted config path, e.g. models.0.api_url"),
+    ("arg.config.get.reveal", "Print api_key as stored instead of masking it"),
+    ("arg.config.set.key", "Dotted config path, e.g. models.0.api_url"),
+    ("arg.config.set.value", "New value"),
+    ("arg.config.unset.key", "Dotted config path, e.g. models.0.api_url"),
+    ("arg.config.model.add.name", "Model name used by /model and personas"),
+    ("arg.config.model.add.model", "Model name sent to the API, defaults to the name"),
+    ("arg.config.model.add.api_url", "API URL"),
+    ("arg.config.model.add.api_key", "API key"),
+    ("arg.config.model.add.api_key_env", "Read the API key from this environment variable"),
+    ("arg.config.model.add.api_key_file", "Read the API key from this file"),
+    ("arg.config.model.add.api_key_cmd", "Run this command and use its output as the API key"),
+    ("arg.config.model.add.description", "Description"),
+    ("arg.config.model.add.provider", "Provider"),
+    ("arg.config.model.add.context_window", "Context window in tokens"),
+    ("arg.config.model.remove.name", "The model's name or model"),
+    ("cmd.mcp", "Rename, delete, pin, move and clean up sessions"),
+    ("cmd.mcp.rename", "Rename a session"),
+    ("cmd.mcp.delete", "Move a session to the trash"),
+    ("cmd.mcp.pin", "Pin a session"),
+    ("cmd.mcp.unpin", "Unpin a session"),
+    ("cmd.mcp.move", "Move a session to a folder, or out of any folder"),
+    ("cmd.mcp.cleanup", "Archive old sessions according to the retention policy"),
+    ("arg.mcp.rename.new_title", "New title"),
+    ("arg.mcp.move.folder", "Folder name"),
+    ("arg.mcp.cleanup.dry_run", "Only list the sessions that would be archived"),
+    ("cmd.list", "List sessions"),
+    ("arg.list.detail", "Show full IDs, times and message counts"),
+    ("arg.list.all", "Include archived sessions"),
+    ("arg.list.tag", "Only sessions with this tag"),
+    ("arg.list.folder", "Only sessions in this folder"),
+    ("arg.list.pinned", "Only pinned sessions"),
+    ("cmd.tag", "Manage session tags"),
+    ("cmd.tag.add", "Add tags to a session"),
+    ("cmd.tag.remove", "Remove tags from a session"),
+    ("cmd.tag.list", "List all tags with usage counts"),
+    ("cmd.resume", "Continue the last session"),
+    ("cmd.restore", "Switch to a session and start chatting"),
+    ("cmd.generate", "Generate files and run commands from the last reply of a session"),
+    ("cmd.export", "Export sessions to a JSON file, the current session by default"),
+    ("arg.export.path", "Output file"),
+    ("arg.export.all", "Export all sessions"),
+    ("arg.export.session_id", "Session to export"),
+    ("cmd.import", "Import sessions from a JSON file"),
+    ("arg.import.path", "File to import"),
+    ("cmd.trash", "Manage the trash"),
+    ("cmd.trash.list", "List sessions in the trash"),
+    ("cmd.trash.restore", "Restore a session from the trash"),
+    ("cmd.trash.empty", "Permanently delete all sessions in the trash"),
+    ("cmd.index", "Build a local retrieval index for a project, used by /rag"),
+    ("arg.index.dir", "Project directory"),
+    ("cmd.search", "Search messages in all sessions"),
+    ("arg.search.query", "Query; supports \"phrase\", role:user, after:YYYY-MM-DD, before:YYYY-MM-DD"),
+    ("arg.search.limit", "Maximum number of results"),
+    ("cmd.completions", "Generate shell completions, e.g. mobius completions bash > /etc/bash_completion.d/mobius"),
+    ("arg.completions.shell", "Target shell"),
+];
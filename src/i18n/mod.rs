@@ -0,0 +1,138 @@
+mod en;
+mod zh_cn;
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Locale {
+    // 接受 zh、zh-CN、zh_CN.UTF-8、en、en_US.UTF-8 等写法
+    pub fn parse(value: &str) -> Option<Locale> {
+        let value = value.trim().to_lowercase();
+        let language = value.split(['-', '_', '.', '@']).next().unwrap_or_default();
+        match language {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::ZhCn => zh_cn::MESSAGES,
+            Locale::En => en::MESSAGES,
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::ZhCn => write!(f, "zh-CN"),
+            Locale::En => write!(f, "en"),
+        }
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+// 未在配置中指定语言时按 LC_ALL、LC_MESSAGES、LANG 的顺序选择，
+// 未设置或为 C/POSIX 时使用中文
+pub fn locale_from_env() -> Option<Locale> {
+    locale_var().and_then(|(_, value)| match value.as_str() {
+        "C" | "POSIX" => None,
+        _ => Some(Locale::parse(&value).unwrap_or(Locale::En)),
+    })
+}
+
+// 决定语言的环境变量名；为 None 时没有可用的环境变量，使用默认的中文
+pub fn locale_env_source() -> Option<&'static str> {
+    locale_from_env().and(locale_var().map(|(name, _)| name))
+}
+
+fn locale_var() -> Option<(&'static str, String)> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok().map(|value| (name, value)))
+        .find(|(_, value)| !value.is_empty())
+}
+
+// 配置文件中的 language 字段；在完整加载配置（以及解析命令行）之前读取，
+// 这样帮助信息和配置错误也能使用所选语言
+pub fn locale_from_config(config_path: &Path) -> Option<Locale> {
+    let content = fs::read_to_string(config_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    Locale::parse(value.get("language")?.as_str()?)
+}
+
+pub fn init(config_path: &Path) {
+    let locale = locale_from_config(config_path)
+        .or_else(locale_from_env)
+        .unwrap_or(Locale::ZhCn);
+    let _ = LOCALE.set(locale);
+}
+
+pub fn locale() -> Locale {
+    *LOCALE.get_or_init(|| locale_from_env().unwrap_or(Locale::ZhCn))
+}
+
+fn find(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, text)| *text)
+}
+
+// 当前语言中的文本，缺少翻译时使用中文，两者都没有时返回 None
+pub fn lookup(key: &str) -> Option<&'static str> {
+    find(locale(), key).or_else(|| find(Locale::ZhCn, key))
+}
+
+pub fn text(key: &'static str) -> &'static str {
+    lookup(key).unwrap_or(key)
+}
+
+// 依次用参数替换文本中的 {}
+pub fn format_message(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// t!("key") 返回当前语言的文本，t!("key", a, b) 用参数依次替换其中的 {}
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::text($key)
+    };
+    ($key:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format_message(
+            $crate::i18n::text($key),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+pub(crate) use t;
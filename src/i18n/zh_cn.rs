@@ -0,0 +1,414 @@
+// 中文文本，同时作为其他语言缺少翻译时的后备
+pub const MESSAGES: &[(&str, &str)] = &[
+    // 通用
+    ("label.error", "错误:"),
+    ("label.warning", "警告:"),
+    ("common.yes", "是"),
+    ("common.no", "否"),
+    ("common.none", "(无)"),
+    ("common.default", "(默认)"),
+    ("common.current", " (当前)"),
+    ("common.cancelled", "已取消"),
+    ("session.default_title", "新会话"),
+    ("session.pinned_label", " [置顶]"),
+    ("session.folder_label", " 目录:{}"),
+    // SessionError
+    ("error.io", "IO错误: {}"),
+    ("error.json", "JSON错误: {}"),
+    ("error.session_not_found", "会话未找到: {}"),
+    ("error.message_not_found", "消息未找到: {}"),
+    ("error.persona_not_found", "角色未找到: {}"),
+    ("error.model_not_found", "模型未找到: {}"),
+    ("error.ambiguous_session", "'{}' 匹配到多个会话:"),
+    ("error.invalid_session_id", "无效的会话ID"),
+    ("error.no_current_session", "没有当前会话"),
+    // SessionManager
+    ("manager.context_failed", "无法生成项目上下文 {}: {}"),
+    ("manager.index_stale", "索引建立后文件已变化，可运行 mobius index {} 重建"),
+    ("manager.search_index_failed", "无法更新搜索索引: {}"),
+    ("manager.sessions_missing", "会话文件不存在"),
+    // 命令行
+    ("cli.load_sessions_failed", "无法加载会话数据: {}"),
+    ("cli.load_state_failed", "无法加载会话状态: {}"),
+    ("cli.session_restored", "已恢复会话: {}"),
+    ("cli.session_created", "已创建新会话: {}"),
+    ("cli.session_continued", "继续上一次会话: {}"),
+    ("cli.session_switched", "已切换到会话: {}"),
+    ("cli.resuming", "正在恢复上一次会话: {}"),
+    ("cli.nothing_to_resume", "没有可恢复的上一次会话"),
+    ("cli.creating_session", "正在创建新会话..."),
+    ("cli.generate_confirm", "确定要生成该对话吗?: {}"),
+    ("persona.current", "当前角色: {}"),
+    ("persona.list_entry", "{}{} 模型: {} 温度: {} top_p: {}"),
+    ("persona.default_marker", " (默认)"),
+    ("persona.exists", "角色已存在: {}"),
+    ("persona.added", "已添加角色: {}"),
+    ("persona.updated", "已更新角色: {}"),
+    ("persona.removed", "已删除角色: {}"),
+    ("persona.default_set", "默认角色已设置为: {}"),
+    ("persona.default_prompt", "你是一个乐于助人的编程助手，回答准确、简洁。"),
+    ("persona.reviewer_prompt", "你是一名资深的代码审查者。指出代码中的缺陷、潜在风险和可改进之处，并给出具体的修改建议。"),
+    ("title.prompt", "请为下面的对话生成一个不超过10个字的简短标题，使用对话所用的语言，只输出标题本身。"),
+    ("config.title", "当前配置:"),
+    ("config.max_sessions", "  最大会话数: {}"),
+    ("config.max_age", "  最长保留天数: {}"),
+    ("config.max_size", "  会话总大小上限(MB): {}"),
+    ("config.auto_save", "  自动保存: {}"),
+    ("config.default_model", "  默认模型: {}"),
+    ("config.api_key", "  API key: {}"),
+    ("config.theme", "  主题: {}"),
+    ("config.language", "  语言: {}"),
+    ("config.language_auto", "{} (来自环境变量 {})"),
+    ("config.language_default", "{} (默认)"),
+    ("config.max_sessions_set", "最大会话数已设置为: {}"),
+    ("config.max_age_set", "会话最长保留天数已设置为: {}"),
+    ("config.max_size_set", "会话总大小上限已设置为: {} MB"),
+    ("config.model_set", "默认模型已设置为: {}"),
+    ("config.unknown_theme", "未知主题 {}，可选 light、dark、system 或 custom_themes 中的名称"),
+    ("config.theme_set", "主题已设置为: {}"),
+    ("config.auto_save_enabled", "自动保存已启用"),
+    ("config.auto_save_disabled", "自动保存已禁用"),
+    ("config.unknown_language", "未知语言 {}，可选 zh-CN、en 或 auto"),
+    ("config.language_set", "界面语言已设置为: {}"),
+    ("config.language_cleared", "界面语言将跟随 LANG 等环境变量"),
+    ("config.unknown_key", "未知配置项: {}"),
+    ("config.invalid_value", "{} 的值无效: {}"),
+    ("config.key_env_missing", "环境变量 {} 未设置"),
+    ("config.key_file_failed", "无法读取 key 文件 {}"),
+    ("config.key_cmd_failed", "无法运行 key 命令 {}"),
+    ("config.key_cmd_status", "key 命令 {} 运行失败: {}"),
+    ("config.value_set", "{} 已设置为: {}"),
+    ("config.value_unset", "{} 已重置，当前值: {}"),
+    ("config.edit_again", "要重新编辑吗?"),
+    ("config.saved", "配置已保存"),
+    ("config.model_entry", "{}{}  模型: {}  接口: {}"),
+    ("config.model_default", " (默认模型)"),
+    ("config.model_exists", "模型已存在: {}"),
+    ("config.model_added", "已添加模型: {}"),
+    ("config.model_removed", "已删除模型: {}"),
+    ("config.model_is_default", "{} 是默认模型，不能删除"),
+    ("check.line", "第 {} 行"),
+    ("check.failed", "配置文件 {} 有错误:"),
+    ("check.passed", "配置检查通过"),
+    ("check.missing", "配置文件 {} 不存在，将使用默认配置"),
+    ("check.repair_prompt", "要用默认值补全或替换出错的字段吗?"),
+    ("check.repair_hint", "可运行 mobius config check 检查并修复配置"),
+    ("check.repaired", "配置已修复，原文件备份为 {}"),
+    ("check.invalid_url", "地址无效: {}"),
+    ("check.unsupported_scheme", "不支持的协议 {}，应为 http 或 https"),
+    ("check.empty_api_key", "api_key 为空，请求会被拒绝"),
+    ("check.key_env_unset", "环境变量 {} 未设置，请求会失败"),
+    ("check.key_file_missing", "key 文件 {} 不存在，请求会失败"),
+    ("check.empty_model", "模型名称为空"),
+    ("check.duplicate_model", "模型名称重复: {}"),
+    ("check.unknown_model", "未配置的模型: {}"),
+    ("check.unknown_persona", "角色不存在: {}"),
+    ("check.unknown_theme", "custom_themes 中没有主题 {}"),
+    ("check.out_of_range", "{} 超出范围 {} 到 {}"),
+    ("check.zero_max_sessions", "最大会话数为 0，每次新建会话都会清理旧会话"),
+    ("session.renamed", "会话 '{}' 已重命名为 '{}'"),
+    ("session.trashed", "会话 '{}' 已移入回收站"),
+    ("session.pinned", "会话 '{}' 已置顶"),
+    ("session.unpinned", "会话 '{}' 已取消置顶"),
+    ("session.folder", "会话 '{}' 的目录: {}"),
+    ("session.tags", "会话 '{}' 的标签: {}"),
+    ("session.restored", "会话 '{}' 已恢复"),
+    ("cleanup.plan", "将清理 {} 个会话:"),
+    ("cleanup.entry", "  {} [ID: {}] 最后访问: {}"),
+    ("cleanup.done", "已清理 {} 个会话并移入归档, 剩余 {} 个会话"),
+    ("list.empty", "没有可用的会话"),
+    ("list.header", "会话:"),
+    ("list.header_all", "所有会话:"),
+    ("list.created_at", "  创建时间: {}"),
+    ("list.last_accessed", "  最后访问: {}"),
+    ("list.message_count", "  消息数量: {}"),
+    ("list.archived_header", "已归档会话:"),
+    ("archive.retention", "保留策略归档"),
+    ("archive.deleted", "已删除"),
+    ("tag.empty", "没有标签"),
+    ("index.building", "正在建立索引: {}"),
+    ("index.done", "已索引 {} 个文件, {} 个片段, 保存到 {}"),
+    ("trash.empty", "回收站为空"),
+    ("trash.entry", "{}. {} [ID: {}] {} 于 {}, 消息数量: {}"),
+    ("trash.confirm_empty", "确定要永久删除回收站中的所有会话吗?"),
+    ("trash.emptied", "已永久删除 {} 个会话"),
+    ("export.all", "已导出 {} 个会话到 {}"),
+    ("export.session", "已导出会话 '{}' 到 {}"),
+    ("export.current", "已导出当前会话到 {}"),
+    ("import.done", "已从 {} 导入会话"),
+    ("search.no_results", "没有找到匹配的消息"),
+    // 对话循环
+    ("loop.unknown_session", "未知会话"),
+    ("loop.header", "会话: {} [ID: {}]"),
+    ("loop.help_hint", "输入 /help 查看可用命令"),
+    ("loop.attachment", "附件: {}"),
+    ("reply.citations", "参考: {}"),
+    ("input.history_failed", "无法保存输入历史: {}"),
+    ("editor.exit_status", "编辑器退出状态: {}"),
+    // REPL 命令
+    ("repl.exit", "已退出，会话已保存"),
+    ("repl.list_header", "\n--- 当前会话列表 ---"),
+    ("repl.current_session", "当前会话: {} [ID: {}]"),
+    ("repl.generated", "对话文件已生成: {}"),
+    ("repl.saved", "会话已手动保存"),
+    ("repl.renamed", "当前会话已重命名为: {}"),
+    ("repl.title", "当前会话标题: {}"),
+    ("repl.invalid_index", "无效的消息序号: {}"),
+    ("repl.forked", "已从第 {} 条消息创建分支，下一条消息将开启新分支"),
+    ("repl.branch_entry", "{}. {} 条消息, 最后提问: {}{}"),
+    ("repl.checked_out", "已切换到分支 {}"),
+    ("repl.branch_not_found", "分支未找到: {}"),
+    ("repl.no_reply", "当前分支还没有回复"),
+    ("repl.nothing_to_retry", "没有可重试的消息"),
+    ("repl.nothing_to_edit", "没有可编辑的消息"),
+    ("repl.editor_failed", "无法打开编辑器: {}"),
+    ("repl.unchanged", "消息未修改"),
+    ("repl.undone", "已撤销最后一轮对话，可通过 /alternatives 或 /branches 查看"),
+    ("repl.nothing_to_undo", "没有可撤销的对话"),
+    ("repl.message_not_found", "消息未找到"),
+    ("repl.versions", "共 {} 个版本:"),
+    ("repl.version_header", "\n--- 版本 {}{} ---"),
+    ("repl.tag_usage", "用法: /tag [add|remove <标签...>]"),
+    ("repl.tags", "当前会话标签: {}"),
+    ("repl.pinned", "当前会话已置顶"),
+    ("repl.unpinned", "当前会话已取消置顶"),
+    ("repl.folder", "当前会话目录: {}"),
+    ("repl.no_attachments", "没有待发送的附件"),
+    ("repl.attachment_entry", "  {} ({} 字节)"),
+    ("repl.attachments_cleared", "已清空待发送的附件"),
+    ("repl.attachment_added", "已添加附件: {}"),
+    ("repl.attachments_pending", "附件将随下一条消息发送"),
+    ("repl.context_pinned", "已固定项目上下文: {} (包含 {}/{} 个文件, 约 {} tokens)"),
+    ("repl.context_removed", "已移除项目上下文: {}"),
+    ("repl.context_not_found", "项目上下文未找到: {}"),
+    ("repl.context_cleared", "已清空项目上下文"),
+    ("repl.context_empty", "当前会话没有固定的项目上下文"),
+    ("repl.context_usage", "用法: /context [list|add <目录>|remove <序号>|clear]"),
+    ("repl.rag_no_index", "提示: 尚未建立索引，请先运行 mobius index {}"),
+    ("repl.rag_on", "已开启检索: {} (每次 {} 个片段)"),
+    ("repl.rag_off", "已关闭检索"),
+    ("repl.rag_status", "检索已开启: {} (每次 {} 个片段)"),
+    ("repl.rag_disabled", "检索未开启"),
+    ("repl.rag_usage", "用法: /rag [on <目录> [片段数]|off]"),
+    ("repl.templates", "可用模板 ({}):"),
+    ("repl.template_sent", "已发送模板 {} ({} 字)"),
+    ("repl.persona_switched", "已切换到角色: {}"),
+    ("repl.personas", "可用角色: {}"),
+    ("repl.model_default", "已恢复使用默认模型: {}"),
+    ("repl.model_switched", "当前会话已切换到模型: {}"),
+    ("repl.model_current", "当前模型: {}"),
+    ("repl.raw_on", "回复将原样输出"),
+    ("repl.raw_off", "回复将渲染 markdown"),
+    ("repl.unknown_command", "未知命令: {}"),
+    (
+        "repl.help",
+        "
+可用命令:
+  /exit             - 退出
+  /list             - 列出所有会话
+  /switch <ID>      - 切换到指定会话，可用ID前缀、#序号或标题
+  /switch -         - 切换回上一个会话
+  /new [标题]       - 创建新会话
+  /save             - 手动保存会话
+  /rename <新标题>  - 重命名当前会话
+  /title            - 显示当前会话标题
+  /history          - 显示当前分支的消息
+  /fork [序号]      - 从指定消息创建分支
+  /branches         - 列出当前会话的分支
+  /checkout <序号>  - 切换到指定分支
+  /last             - 按当前终端宽度重新显示最后一条回复
+  /retry            - 重新生成最后一条回复
+  /edit             - 编辑最后一条消息并重新生成
+  /undo             - 撤销最后一轮对话
+  /alternatives [序号] - 查看消息的其他版本
+  /tag [add|remove <标签...>] - 查看或修改当前会话标签
+  /pin, /unpin      - 置顶或取消置顶当前会话
+  /folder [目录]    - 查看或设置当前会话目录
+  /file <路径...>   - 添加附件到下一条消息，支持通配符；/file clear 清空
+  @路径             - 在消息中直接引用文件作为附件
+  /context add <目录> - 固定项目上下文，每轮对话都会附带
+  /context [list|remove <序号>|clear] - 管理项目上下文
+  /rag [on <目录> [片段数]|off] - 每条消息从项目索引中检索相关代码
+  /t [模板] [变量=值...] - 列出模板或渲染并发送模板
+  /persona [名称]   - 查看或切换当前会话的角色
+  /model [名称|default] - 查看或切换当前会话的模型
+  /search <关键词>  - 搜索所有会话，支持 \"短语\" role:user after:/before:YYYY-MM-DD
+  /raw              - 切换回复的原样输出和 markdown 渲染
+  /config           - 显示当前配置
+  /help             - 显示帮助
+
+输入:
+  Alt-Enter         - 换行而不发送
+  \"\"\"               - 单独一行开始和结束多行消息
+  Ctrl-R            - 反向搜索输入历史
+  Ctrl-C / Ctrl-D   - 清空当前输入 / 退出",
+    ),
+    // 代码块生成文件和执行命令
+    ("generate.confirm_files", "请选择是否需要生成文件"),
+    ("generate.file_failed", "生成文件失败: {}"),
+    ("generate.not_selected", "未选择"),
+    ("generate.confirm_commands", "检测到 {} 条命令，是否执行？"),
+    ("generate.command_error", "执行命令出错: {}"),
+    ("generate.confirm_command", "检测到 {} 命令，是否执行？"),
+    ("generate.command_failed", "命令执行失败: {}"),
+    ("generate.spawn_failed", "无法执行命令: {}"),
+    ("generate.not_executed", "未执行命令"),
+    ("generate.missing_path", "代码块缺少 path 元数据"),
+    ("generate.file_done", "文件已生成: {}"),
+    ("parser.start", "开始解析文件内容..."),
+    ("parser.done", "文件解析完成，生成文件数量: {}"),
+    // 附件、项目上下文、模板和搜索
+    ("files.not_found", "文件不存在: {}"),
+    ("files.invalid_glob", "无效的通配符: {}"),
+    ("files.read_failed", "无法读取文件: {}"),
+    ("files.too_large", "文件过大 ({} KB): {}"),
+    ("files.binary", "跳过二进制文件: {}"),
+    ("files.no_match", "没有匹配的文件: {}"),
+    ("files.total_too_large", "附件总大小超过 {} KB，已忽略剩余文件"),
+    ("project.dir_missing", "目录不存在: {}"),
+    ("project.not_dir", "不是目录: {}"),
+    ("rag.no_index", "尚未建立索引，请先运行 mobius index {}"),
+    ("project.context_header", "以下是项目 {} 的上下文（已包含 {}/{} 个文件的内容）。\n\n文件结构:\n{}\n\n{}"),
+    ("project.tree_more", "... 另有 {} 个文件"),
+    ("rag.chunks_header", "以下是从项目中检索到的与问题相关的代码片段，回答时如引用请注明 文件:行号。\n\n{}"),
+    ("template.not_found", "模板未找到: {}"),
+    ("template.unclosed", "模板中有未闭合的 {{"),
+    ("template.spawn_failed", "无法执行命令: {}"),
+    ("template.command_failed", "命令执行失败: {}\n{}"),
+    ("template.missing_variable", "缺少模板变量: {}"),
+    ("template.default_review", "请审查下面的代码，指出缺陷、潜在风险和改进建议：\n\n文件: {{path}}\n\n```\n{{file:{{path}}}}\n```\n"),
+    ("template.default_review_diff", "请审查以下改动，指出问题并给出修改建议：\n\n```diff\n{{shell:git diff}}\n```\n"),
+    ("template.default_translate", "请将下面的内容翻译成{{language}}，保持原有格式：\n\n{{text}}\n"),
+    ("template.default_tests", "请为下面的代码编写单元测试，覆盖正常路径和边界情况：\n\n```\n{{file:{{path}}}}\n```\n"),
+    ("search.empty_query", "搜索内容为空"),
+    ("search.invalid_date", "无效的日期: {} (格式: YYYY-MM-DD)"),
+    ("search.index_failed", "无法更新搜索索引"),
+    ("theme.invalid_color", "主题 {} 中的颜色无效: {}"),
+    ("ask.empty", "没有提示词，请在参数中提供或通过标准输入传入"),
+    // 全屏界面
+    ("tui.you", "你"),
+    ("tui.assistant", "助手"),
+    ("tui.sessions", " 会话 ({})"),
+    ("tui.input_title", " 输入 (Enter 发送, Alt-Enter 换行) "),
+    ("tui.rename_title", " 重命名会话 (Enter 确认, Esc 取消) "),
+    ("tui.streaming_title", " 正在生成 (Esc 取消) "),
+    ("tui.persona", "| 角色: {} "),
+    ("tui.context", "| 上下文≈{} tokens "),
+    ("tui.usage", "| 上次: 输入 {} 输出 {} "),
+    ("tui.confirm_delete", "删除会话 {}? (y/n)"),
+    ("tui.sidebar_hints", "↑↓ 选择 · Enter 切换 · n 新建 · r 重命名 · d 删除 · Tab 返回"),
+    ("tui.hints", "Tab 会话列表 · Ctrl-N 新建 · F2 重命名 · PgUp/PgDn 滚动 · Ctrl-Q 退出"),
+    ("tui.delete_cancelled", "已取消删除"),
+    ("tui.session_created", "已创建新会话: {}"),
+    ("tui.renamed", "已重命名为: {}"),
+    ("tui.trashed", "会话已移入回收站"),
+    ("tui.attached", "已附加 {} 个文件"),
+    ("tui.generation_cancelled", "已取消生成"),
+    // 命令行帮助
+    ("clap.usage", "用法:"),
+    ("clap.commands", "命令"),
+    ("clap.options", "选项"),
+    ("clap.arguments", "参数"),
+    ("arg.help", "显示帮助"),
+    ("arg.version", "显示版本"),
+    ("arg.raw", "原样输出回复，不渲染 markdown"),
+    ("arg.output", "命令结果的输出格式：table 为可读文本，plain 为制表符分隔的记录，json 供脚本读取"),
+    ("arg.session_id", "会话ID、ID前缀、#序号或标题"),
+    ("arg.name", "角色名称"),
+    ("arg.prompt", "系统提示词"),
+    ("arg.model", "使用的模型（Config.models 中的 name 或 model）"),
+    ("arg.temperature", "采样温度"),
+    ("arg.top_p", "核采样概率"),
+    ("arg.tags", "标签"),
+    ("cmd.mobius", "一个会话管理系统"),
+    ("cmd.help", "显示帮助，或指定子命令的帮助"),
+    ("cmd.start", "开始对话，默认继续上一次会话"),
+    ("arg.start.title", "以该标题创建新会话，不继续上一次会话"),
+    ("arg.start.restore", "恢复指定的会话（ID前缀、#序号或标题）"),
+    ("arg.start.persona", "当前会话使用的角色"),
+    ("cmd.tui", "全屏界面"),
+    ("cmd.ask", "单次提问：提示词之后附加标准输入的内容，回复只输出到标准输出"),
+    ("arg.ask.prompt", "提示词，可省略而只使用标准输入"),
+    ("arg.ask.session", "继续指定的会话（ID前缀、#序号或标题）"),
+    ("arg.ask.no_save", "使用临时会话，不保存"),
+    ("cmd.persona", "管理角色"),
+    ("cmd.persona.list", "列出所有角色"),
+    ("cmd.persona.add", "添加角色，未指定 --prompt 时打开编辑器"),
+    ("cmd.persona.edit", "修改角色，未指定任何选项时打开编辑器修改系统提示词"),
+    ("cmd.persona.remove", "删除角色"),
+    ("cmd.persona.set-default", "设置新会话使用的角色"),
+    ("cmd.config", "查看和修改配置"),
+    ("cmd.config.show", "显示当前配置"),
+    ("cmd.config.set-max-sessions", "设置最大会话数"),
+    ("cmd.config.set-max-age", "设置会话最长保留天数，0 表示不限制"),
+    ("cmd.config.set-max-size", "设置会话总大小上限（MB），0 表示不限制"),
+    ("cmd.config.set-model", "设置默认模型名称"),
+    ("cmd.config.set-theme", "设置主题：light、dark、system 或 custom_themes 中的名称"),
+    ("cmd.config.toggle-auto-save", "启用或禁用自动保存"),
+    ("cmd.config.set-language", "设置界面语言：zh-CN、en，或 auto 跟随 LANG 等环境变量"),
+    ("cmd.config.get", "按点号路径读取配置项，例如 default_model.api_url"),
+    ("cmd.config.set", "修改配置项，值先按 JSON 解析，解析失败时作为字符串"),
+    ("cmd.config.unset", "删除配置项，必填项恢复为默认值"),
+    ("cmd.config.edit", "用 $EDITOR 编辑配置文件，保存时检查格式"),
+    ("cmd.config.check", "检查配置文件，有错误时可用默认值修复"),
+    ("cmd.config.model", "管理 Config.models 中的模型"),
+    ("cmd.config.model.list", "列出所有模型"),
+    ("cmd.config.model.add", "添加模型，未指定 --api-url、--api-key 时沿用默认模型的设置"),
+    ("cmd.config.model.remove", "删除模型"),
+    ("arg.config.get.key", "点号分隔的配置路径，例如 models.0.api_url"),
+    ("arg.config.get.reveal", "原样显示 api_key，默认只显示首尾几个字符"),
+    ("arg.config.set.key", "点号分隔的配置路径，例如 models.0.api_url"),
+    ("arg.config.set.value", "新的值"),
+    ("arg.config.unset.key", "点号分隔的配置路径，例如 models.0.api_url"),
+    ("arg.config.model.add.name", "模型名称，用于 /model 和角色配置"),
+    ("arg.config.model.add.model", "接口中的模型名称，默认与名称相同"),
+    ("arg.config.model.add.api_url", "接口地址"),
+    ("arg.config.model.add.api_key", "接口的 API key"),
+    ("arg.config.model.add.api_key_env", "从该环境变量读取 API key"),
+    ("arg.config.model.add.api_key_file", "从该文件读取 API key"),
+    ("arg.config.model.add.api_key_cmd", "运行该命令，以输出作为 API key"),
+    ("arg.config.model.add.description", "模型说明"),
+    ("arg.config.model.add.provider", "模型提供方"),
+    ("arg.config.model.add.context_window", "上下文窗口（token）"),
+    ("arg.config.model.remove.name", "模型的 name 或 model"),
+    ("cmd.mcp", "重命名、删除、置顶、移动和清理会话"),
+    ("cmd.mcp.rename", "重命名会话"),
+    ("cmd.mcp.delete", "将会话移入回收站"),
+    ("cmd.mcp.pin", "置顶会话"),
+    ("cmd.mcp.unpin", "取消置顶"),
+    ("cmd.mcp.move", "将会话移到目录，不指定目录时移出目录"),
+    ("cmd.mcp.cleanup", "按保留策略归档旧会话"),
+    ("arg.mcp.rename.new_title", "新标题"),
+    ("arg.mcp.move.folder", "目录名称"),
+    ("arg.mcp.cleanup.dry_run", "只列出将被清理的会话，不实际移除"),
+    ("cmd.list", "列出会话"),
+    ("arg.list.detail", "显示完整ID、时间和消息数量"),
+    ("arg.list.all", "同时列出已归档的会话"),
+    ("arg.list.tag", "只显示带有该标签的会话"),
+    ("arg.list.folder", "只显示该目录中的会话"),
+    ("arg.list.pinned", "只显示置顶的会话"),
+    ("cmd.tag", "管理会话标签"),
+    ("cmd.tag.add", "为会话添加标签"),
+    ("cmd.tag.remove", "移除会话的标签"),
+    ("cmd.tag.list", "列出所有标签及使用次数"),
+    ("cmd.resume", "继续上一次会话"),
+    ("cmd.restore", "切换到指定会话并开始对话"),
+    ("cmd.generate", "根据会话的最后一条回复生成文件、执行命令"),
+    ("cmd.export", "导出会话到 JSON 文件，默认导出当前会话"),
+    ("arg.export.path", "导出文件路径"),
+    ("arg.export.all", "导出所有会话"),
+    ("arg.export.session_id", "要导出的会话"),
+    ("cmd.import", "从 JSON 文件导入会话"),
+    ("arg.import.path", "要导入的文件"),
+    ("cmd.trash", "管理回收站"),
+    ("cmd.trash.list", "列出回收站中的会话"),
+    ("cmd.trash.restore", "从回收站恢复会话"),
+    ("cmd.trash.empty", "永久删除回收站中的所有会话"),
+    ("cmd.index", "为项目建立本地检索索引，配合 /rag 使用"),
+    ("arg.index.dir", "项目目录"),
+    ("cmd.search", "搜索所有会话的消息"),
+    ("arg.search.query", "关键词，支持 \"短语\"、role:user、after:YYYY-MM-DD、before:YYYY-MM-DD"),
+    ("arg.search.limit", "最多显示的结果数"),
+    ("cmd.completions", "生成 shell 补全脚本，例如 mobius completions bash > /etc/bash_completion.d/mobius"),
+    ("arg.completions.shell", "目标 shell"),
+];
@@ -1,12 +1,22 @@
 use crate::cli::alter::Alter;
 use std::{error::Error};
 mod cli;
+mod context;
+mod i18n;
 mod markdown;
 mod models;
+mod search;
 mod session;
+mod tui;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    // 输出接到 head 等提前退出的管道时直接结束，而不是因写入失败而 panic
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+
     let app = Alter::new();
     app.run().await?;
     Ok(())
@@ -1,3 +1,4 @@
+use crate::i18n::t;
 use crate::markdown::parser::FileMetadata;
 use crate::markdown::{parser::FileParser};
 use inquire::{Select};
@@ -9,48 +10,51 @@ use tokio::io::AsyncWriteExt;
 
 
 pub async fn judgement_generate_file_async(file_parser: &FileParser) {
-    let generate = Select::new("请选择是否需要生成文件", vec!["是", "否"]).prompt();
+    let yes = t!("common.yes");
+    let generate = Select::new(t!("generate.confirm_files"), vec![yes, t!("common.no")]).prompt();
 
     match generate {
         Ok(g) => {
-            if g == "是" {
+            if g == yes {
                 for file in &file_parser.files {
                     if let Err(e) = generate_file_async(file).await {
-                        eprintln!("Error generating file: {}", e);
+                        eprintln!("{}", t!("generate.file_failed", e));
                     }
                 }
             }
         }
-        Err(_) => println!("未选择模板"),
+        Err(_) => println!("{}", t!("generate.not_selected")),
     }
 }
 
 pub async fn judgement_run_command_async(file_parser: &FileParser) {
-    let message = format!("检测到 {} 条命令，是否执行？", file_parser.commands.len());
-    let generate = Select::new(&message, vec!["是", "否"]).prompt();
+    let message = t!("generate.confirm_commands", file_parser.commands.len());
+    let yes = t!("common.yes");
+    let generate = Select::new(&message, vec![yes, t!("common.no")]).prompt();
 
     match generate {
         Ok(g) => {
-            if g == "是" {
+            if g == yes {
                 for command in &file_parser.commands {
                     if let Err(e) = run_command_async(command).await {
-                        eprintln!("Error running command: {}", e);
+                        eprintln!("{}", t!("generate.command_error", e));
                     }
                 }
             }
         }
-        Err(_) => println!("未选择模板"),
+        Err(_) => println!("{}", t!("generate.not_selected")),
     }
 }
 
 pub async fn run_command_async(command: &String) -> Result<(), io::Error> {
 
-    let message = format!("检测到 {} 命令，是否执行？", command);
-    let generate = Select::new(&message, vec!["是", "否"]).prompt();
+    let message = t!("generate.confirm_command", command);
+    let yes = t!("common.yes");
+    let generate = Select::new(&message, vec![yes, t!("common.no")]).prompt();
 
     match generate {
         Ok(g) => {
-            if g == "是" {
+            if g == yes {
                 let command_str = if cfg!(target_os = "windows") {
                     format!("cmd /C {}", &command.as_str())
                 } else {
@@ -58,7 +62,7 @@ pub async fn run_command_async(command: &String) -> Result<(), io::Error> {
                 };
 
                 let output = if cfg!(target_os = "windows") {
-                    Command::new("cmd").args(&["/C", &command_str]).output().await
+                    Command::new("cmd").args(["/C", &command_str]).output().await
                 } else {
                     Command::new("sh").arg("-c").arg(&command_str).output().await
                 };
@@ -66,18 +70,18 @@ pub async fn run_command_async(command: &String) -> Result<(), io::Error> {
                 match output {
                     Ok(output) => {
                         if !output.status.success() {
-                            eprintln!("Command failed: {}", command);
+                            eprintln!("{}", t!("generate.command_failed", command));
                         }
                         return Ok(());
                     }
                     Err(e) => {
-                        eprintln!("Failed to execute command: {}", e);
+                        eprintln!("{}", t!("generate.spawn_failed", e));
                         return Err(e);
                     }
                 }
             }
         }
-        Err(_) => println!("未执行命令"),
+        Err(_) => println!("{}", t!("generate.not_executed")),
     }
 
     Ok(())
@@ -87,10 +91,7 @@ pub async fn run_command_async(command: &String) -> Result<(), io::Error> {
 
 pub async fn generate_file_async(file: &FileMetadata) -> Result<(), io::Error> {
     if !file.meta_data.contains_key("path") {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "code block missing path metadata",
-        ));
+        return Err(io::Error::other(t!("generate.missing_path")));
     }
     
     let path = file.meta_data.get("path").unwrap();
@@ -109,6 +110,6 @@ pub async fn generate_file_async(file: &FileMetadata) -> Result<(), io::Error> {
     let mut file_handle = async_fs::File::create(&path).await?;
     file_handle.write_all(content).await?;
 
-    println!("File generated successfully at: {:?}", &path);
+    println!("{}", t!("generate.file_done", format!("{:?}", &path)));
     Ok(())
 }
\ No newline at end of file
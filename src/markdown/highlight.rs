@@ -0,0 +1,141 @@
+use crossterm::style::Stylize;
+
+use crate::session::theme::palette;
+
+// 常见语言的关键字，未知语言只高亮字符串、数字和注释
+fn keywords(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "rust" | "rs" => &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+            "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+            "trait", "true", "type", "unsafe", "use", "where", "while",
+        ],
+        "python" | "py" => &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+            "elif", "else", "except", "False", "finally", "for", "from", "global", "if",
+            "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise",
+            "return", "True", "try", "while", "with", "yield",
+        ],
+        "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+            "delete", "do", "else", "export", "extends", "false", "finally", "for", "from",
+            "function", "if", "import", "in", "instanceof", "interface", "let", "new", "null",
+            "return", "super", "switch", "this", "throw", "true", "try", "type", "typeof",
+            "undefined", "var", "void", "while", "yield",
+        ],
+        "go" | "golang" => &[
+            "break", "case", "chan", "const", "continue", "default", "defer", "else", "false",
+            "for", "func", "go", "goto", "if", "import", "interface", "map", "nil", "package",
+            "range", "return", "select", "struct", "switch", "true", "type", "var",
+        ],
+        "c" | "h" | "cpp" | "c++" | "hpp" | "cc" | "java" | "cs" | "csharp" | "kotlin" | "kt" => &[
+            "auto", "bool", "break", "case", "catch", "char", "class", "const", "continue",
+            "default", "delete", "do", "double", "else", "enum", "extends", "false", "final",
+            "float", "for", "if", "implements", "import", "int", "long", "namespace", "new",
+            "null", "nullptr", "private", "protected", "public", "return", "short", "static",
+            "struct", "switch", "template", "this", "throw", "true", "try", "typedef", "using",
+            "virtual", "void", "while",
+        ],
+        "bash" | "sh" | "shell" | "zsh" | "console" => &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+            "if", "in", "local", "return", "then", "until", "while",
+        ],
+        "sql" => &[
+            "AND", "AS", "BY", "CREATE", "DELETE", "DESC", "FROM", "GROUP", "INSERT", "INTO",
+            "JOIN", "LEFT", "LIMIT", "NOT", "NULL", "ON", "OR", "ORDER", "SELECT", "SET",
+            "TABLE", "UPDATE", "VALUES", "WHERE",
+        ],
+        _ => &[],
+    }
+}
+
+fn line_comment(lang: &str) -> Option<&'static str> {
+    match lang {
+        "python" | "py" | "bash" | "sh" | "shell" | "zsh" | "console" | "toml" | "yaml"
+        | "yml" | "ruby" | "rb" => Some("#"),
+        "sql" | "lua" => Some("--"),
+        "rust" | "rs" | "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" | "go"
+        | "golang" | "c" | "h" | "cpp" | "c++" | "hpp" | "cc" | "java" | "cs" | "csharp"
+        | "kotlin" | "kt" | "json" | "jsonc" => Some("//"),
+        _ => None,
+    }
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// 对代码块中的一行做简单的词法高亮，逐行处理，不跟踪跨行的字符串和注释
+pub fn highlight_line(line: &str, lang: &str) -> String {
+    let palette = palette();
+    let lang = lang.to_lowercase();
+    let keywords = keywords(&lang);
+    let comment = line_comment(&lang);
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest: String = chars[i..].iter().collect();
+
+        if let Some(marker) = comment
+            && rest.starts_with(marker)
+        {
+            out.push_str(&format!("{}", rest.with(palette.comment)));
+            break;
+        }
+
+        if c == '"' || c == '\'' || c == '`' {
+            // Rust 的生命周期 'a 不是字符串
+            let is_lifetime = c == '\''
+                && (lang == "rust" || lang == "rs")
+                && chars.get(i + 2) != Some(&'\'')
+                && chars.get(i + 1) != Some(&'\\');
+            let close = (i + 1..chars.len())
+                .find(|&j| chars[j] == c && chars[j - 1] != '\\');
+            if let (Some(close), false) = (close, is_lifetime) {
+                let literal: String = chars[i..=close].iter().collect();
+                out.push_str(&format!("{}", literal.with(palette.string)));
+                i = close + 1;
+                continue;
+            }
+        }
+
+        if c.is_ascii_digit() && (i == 0 || !is_ident(chars[i - 1])) {
+            let end = (i..chars.len())
+                .find(|&j| !(is_ident(chars[j]) || chars[j] == '.'))
+                .unwrap_or(chars.len());
+            let number: String = chars[i..end].iter().collect();
+            out.push_str(&format!("{}", number.with(palette.number)));
+            i = end;
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let end = (i..chars.len()).find(|&j| !is_ident(chars[j])).unwrap_or(chars.len());
+            let word: String = chars[i..end].iter().collect();
+            let is_keyword = if lang == "sql" {
+                keywords.contains(&word.to_uppercase().as_str())
+            } else {
+                keywords.contains(&word.as_str())
+            };
+            if is_keyword {
+                out.push_str(&format!("{}", word.with(palette.keyword).bold()));
+            } else if chars.get(end) == Some(&'(') || chars.get(end) == Some(&'!') {
+                out.push_str(&format!("{}", word.with(palette.function)));
+            } else if !keywords.is_empty() && c.is_uppercase() {
+                out.push_str(&format!("{}", word.with(palette.type_name)));
+            } else {
+                out.push_str(&word);
+            }
+            i = end;
+            continue;
+        }
+
+        out.push(c);
+        i += 1;
+    }
+    out
+}
@@ -1,2 +1,4 @@
 pub mod generate;
+pub mod highlight;
 pub mod parser;
+pub mod render;
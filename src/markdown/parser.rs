@@ -3,6 +3,7 @@ use std::result::Result::Ok;
 use regex::Regex;
 use crate::markdown::generate::{judgement_generate_file_async, judgement_run_command_async};
 use anyhow::{Context, Result};
+use crate::i18n::t;
 
 #[derive(Debug)]
 pub struct FileMetadata {
@@ -19,7 +20,7 @@ pub struct FileMetadata {
 /// action: create
 /// -->
 /// import { ArrowRightIcon } from '@heroicons/react/24/outline'
-
+///
 /// export default function Home() {
 ///   return (
 ///     <div className="min-h-screen bg-gray-50">
@@ -28,7 +29,7 @@ pub struct FileMetadata {
 ///   )
 /// }
 /// ```
-
+///
 /// ### 2. 路由配置 `src/App.tsx`
 /// ```typescript
 /// <!-- FILE_METADATA
@@ -37,7 +38,7 @@ pub struct FileMetadata {
 /// -->
 /// import { BrowserRouter as Router, Routes, Route } from 'react-router-dom'
 /// import Home from './pages/Home'
-
+///
 /// function App() {
 ///   return (
 ///     <Router>
@@ -47,10 +48,10 @@ pub struct FileMetadata {
 ///     </Router>
 ///   )
 /// }
-
+///
 /// export default App
 /// ```
-
+///
 /// ### 3. 路由安装命令
 /// ```bash
 /// <!-- FILE_METADATA
@@ -59,9 +60,9 @@ pub struct FileMetadata {
 /// -->
 /// npm install react-router-dom @types/react-router-dom
 /// ```
-
+///
 /// ---
-
+///
 /// ### 调整说明：
 /// 1. **严格遵循方案1**：所有文件均包含YAML元数据块
 /// 2. **元数据位置**：固定在文件起始位置，格式统一为注释包裹
@@ -89,7 +90,7 @@ impl FileParser {
         judgement_generate_file_async(&markdown).await;
         judgement_run_command_async(&markdown).await;
 
-        println!("文件解析完成，生成文件数量: {:#?}", markdown);
+        println!("{}", t!("parser.done", format!("{:#?}", markdown)));
         Ok(())
     }
 
@@ -99,7 +100,7 @@ impl FileParser {
     }
 
     fn split_code(&mut self, content: String) -> Result<()> {
-        println!("开始解析文件内容...");
+        println!("{}", t!("parser.start"));
         let re = Regex::new(r"```[\s\S]*?```")
             .context("Failed to compile code block regex")?;
         let code_blocks = re.captures_iter(&content);
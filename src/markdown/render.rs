@@ -0,0 +1,696 @@
+use crossterm::cursor::{MoveToColumn, MoveUp};
+use crossterm::queue;
+use crossterm::style::{Color, ContentStyle, Stylize};
+use crossterm::terminal::{self, Clear, ClearType};
+use std::io::{self, Write};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::markdown::highlight::highlight_line;
+use crate::session::theme::{palette, stdout_styled};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SpanStyle {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+    link: bool,
+    dim: bool,
+    color: Option<Color>,
+}
+
+#[derive(Debug, Clone)]
+struct Span {
+    text: String,
+    style: SpanStyle,
+}
+
+fn styled(text: &str, style: SpanStyle) -> String {
+    let palette = palette();
+    let color = if style.code {
+        palette.code
+    } else if style.link {
+        palette.link
+    } else if style.dim {
+        palette.border
+    } else {
+        style.color.unwrap_or(palette.assistant)
+    };
+
+    let mut content = ContentStyle::new();
+    if color != Color::Reset {
+        content = content.with(color);
+    }
+    if style.bold {
+        content = content.bold();
+    }
+    if style.italic {
+        content = content.italic();
+    }
+    if style.strike {
+        content = content.crossed_out();
+    }
+    if style.link {
+        content = content.underlined();
+    }
+    if content == ContentStyle::new() {
+        return text.to_string();
+    }
+    format!("{}", content.apply(text))
+}
+
+fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(|s| s.text.width()).sum()
+}
+
+fn render_spans(spans: &[Span]) -> String {
+    spans.iter().map(|s| styled(&s.text, s.style)).collect()
+}
+
+// 行内语法：**粗体**、*斜体*、~~删除线~~、`代码`、[链接](url)
+fn parse_inline(text: &str, base: SpanStyle) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut buf = String::new();
+    let mut style = base;
+    let mut i = 0;
+
+    fn flush(spans: &mut Vec<Span>, buf: &mut String, style: SpanStyle) {
+        if !buf.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(buf),
+                style,
+            });
+        }
+    }
+    let find = |from: usize, pattern: &[char]| -> Option<usize> {
+        (from..chars.len().saturating_sub(pattern.len() - 1))
+            .find(|&j| chars[j..j + pattern.len()] == *pattern)
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '\\' && next.is_some_and(|n| n.is_ascii_punctuation()) {
+            buf.extend(next);
+            i += 2;
+            continue;
+        }
+
+        if c == '`'
+            && let Some(close) = find(i + 1, &['`'])
+        {
+            flush(&mut spans, &mut buf, style);
+            spans.push(Span {
+                text: chars[i + 1..close].iter().collect(),
+                style: SpanStyle { code: true, ..style },
+            });
+            i = close + 1;
+            continue;
+        }
+
+        // 成对出现时才视为强调标记
+        if (c == '*' || c == '_') && next == Some(c) {
+            if style.bold || find(i + 2, &[c, c]).is_some() {
+                flush(&mut spans, &mut buf, style);
+                style.bold = !style.bold;
+                i += 2;
+                continue;
+            }
+        } else if c == '~' && next == Some('~') {
+            if style.strike || find(i + 2, &['~', '~']).is_some() {
+                flush(&mut spans, &mut buf, style);
+                style.strike = !style.strike;
+                i += 2;
+                continue;
+            }
+        } else if c == '*' || c == '_' {
+            // snake_case 中的下划线不是斜体
+            let word_before = i > 0 && chars[i - 1].is_alphanumeric();
+            let word_after = next.is_some_and(|n| n.is_alphanumeric());
+            let opens = !style.italic
+                && next.is_some_and(|n| !n.is_whitespace())
+                && !(c == '_' && word_before)
+                && find(i + 1, &[c]).is_some();
+            let closes = style.italic && i > 0 && !chars[i - 1].is_whitespace() && !(c == '_' && word_after);
+            if opens || closes {
+                flush(&mut spans, &mut buf, style);
+                style.italic = !style.italic;
+                i += 1;
+                continue;
+            }
+        }
+
+        if c == '['
+            && let Some(mid) = find(i + 1, &[']', '('])
+            && let Some(close) = find(mid + 2, &[')'])
+        {
+            flush(&mut spans, &mut buf, style);
+            let label: String = chars[i + 1..mid].iter().collect();
+            let url: String = chars[mid + 2..close].iter().collect();
+            spans.push(Span {
+                text: label.clone(),
+                style: SpanStyle { link: true, ..style },
+            });
+            if label != url {
+                spans.push(Span {
+                    text: format!(" ({})", url),
+                    style: SpanStyle { dim: true, ..style },
+                });
+            }
+            i = close + 1;
+            continue;
+        }
+
+        buf.push(c);
+        i += 1;
+    }
+    flush(&mut spans, &mut buf, style);
+    spans
+}
+
+// 行首的前缀（列表符号、引用线），折行后的行用等宽的空白或引用线对齐
+struct Prefix {
+    first: String,
+    rest: String,
+    width: usize,
+}
+
+impl Prefix {
+    fn none() -> Self {
+        Prefix {
+            first: String::new(),
+            rest: String::new(),
+            width: 0,
+        }
+    }
+}
+
+// 按终端宽度折行，英文单词尽量不拆开，中文按字符断行
+fn wrap(spans: &[Span], width: usize, prefix: &Prefix) -> Vec<String> {
+    let available = width.saturating_sub(prefix.width).max(10);
+    let mut lines: Vec<Vec<Span>> = vec![Vec::new()];
+    let mut line_width = 0;
+
+    // 拆成单词、空格和单个宽字符
+    let mut tokens: Vec<Span> = Vec::new();
+    for span in spans {
+        let mut word = String::new();
+        for c in span.text.chars() {
+            if c.is_whitespace() || c.width().unwrap_or(0) > 1 {
+                if !word.is_empty() {
+                    tokens.push(Span {
+                        text: std::mem::take(&mut word),
+                        style: span.style,
+                    });
+                }
+                tokens.push(Span {
+                    text: if c.is_whitespace() { " ".to_string() } else { c.to_string() },
+                    style: span.style,
+                });
+            } else {
+                word.push(c);
+            }
+        }
+        if !word.is_empty() {
+            tokens.push(Span {
+                text: word,
+                style: span.style,
+            });
+        }
+    }
+
+    for token in tokens {
+        let token_width = token.text.width();
+        if token.text == " " {
+            if line_width > 0 && line_width < available {
+                lines.last_mut().unwrap().push(token);
+                line_width += 1;
+            }
+            continue;
+        }
+
+        if line_width + token_width > available && line_width > 0 {
+            let line = lines.last_mut().unwrap();
+            while line.last().is_some_and(|s| s.text == " ") {
+                line.pop();
+            }
+            lines.push(Vec::new());
+            line_width = 0;
+        }
+
+        if token_width > available {
+            // 超长的单词按字符拆开
+            for c in token.text.chars() {
+                let w = c.width().unwrap_or(0);
+                if line_width + w > available {
+                    lines.push(Vec::new());
+                    line_width = 0;
+                }
+                lines.last_mut().unwrap().push(Span {
+                    text: c.to_string(),
+                    style: token.style,
+                });
+                line_width += w;
+            }
+            continue;
+        }
+
+        lines.last_mut().unwrap().push(token);
+        line_width += token_width;
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let lead = if i == 0 { &prefix.first } else { &prefix.rest };
+            format!("{}{}", lead, render_spans(&merge(line)))
+        })
+        .collect()
+}
+
+// 合并相邻的同样式片段，减少转义序列
+fn merge(spans: &[Span]) -> Vec<Span> {
+    let mut merged: Vec<Span> = Vec::new();
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if last.style == span.style => last.text.push_str(&span.text),
+            _ => merged.push(span.clone()),
+        }
+    }
+    merged
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+fn split_row(row: &str) -> Vec<String> {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = if row.ends_with('|') && !row.ends_with("\\|") {
+        &row[..row.len() - 1]
+    } else {
+        row
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut escaped = false;
+    for c in row.chars() {
+        if c == '|' && !escaped {
+            cells.push(cell.trim().to_string());
+            cell.clear();
+        } else {
+            cell.push(c);
+        }
+        escaped = c == '\\';
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+fn separator_alignments(cells: &[String]) -> Option<Vec<Align>> {
+    cells
+        .iter()
+        .map(|cell| {
+            let inner = cell.trim_matches(':');
+            if inner.is_empty() || !inner.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Align::Center,
+                (false, true) => Align::Right,
+                _ => Align::Left,
+            })
+        })
+        .collect()
+}
+
+// 表格按显示宽度对齐，中文按两列计算
+fn render_table(rows: &[String]) -> Vec<String> {
+    let mut rows: Vec<Vec<String>> = rows.iter().map(|r| split_row(r)).collect();
+    let alignments = rows.get(1).and_then(|r| separator_alignments(r));
+    let has_header = alignments.is_some();
+    if has_header {
+        rows.remove(1);
+    }
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let cells: Vec<Vec<Vec<Span>>> = rows
+        .iter()
+        .enumerate()
+        .map(|(r, row)| {
+            let base = SpanStyle {
+                bold: has_header && r == 0,
+                ..SpanStyle::default()
+            };
+            (0..columns)
+                .map(|c| parse_inline(row.get(c).map(String::as_str).unwrap_or(""), base))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| cells.iter().map(|row| spans_width(&row[c])).max().unwrap_or(0))
+        .collect();
+    let alignments = alignments.unwrap_or_default();
+
+    let border = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}", format!("{}{}{}", left, segments.join(mid), right).with(palette().border))
+    };
+    let bar = format!("{}", "│".with(palette().border));
+
+    let mut out = vec![border("┌", "┬", "┐")];
+    for (r, row) in cells.iter().enumerate() {
+        let mut line = bar.clone();
+        for (c, spans) in row.iter().enumerate() {
+            let padding = widths[c] - spans_width(spans);
+            let (left, right) = match alignments.get(c).copied().unwrap_or(Align::Left) {
+                Align::Left => (0, padding),
+                Align::Right => (padding, 0),
+                Align::Center => (padding / 2, padding - padding / 2),
+            };
+            line.push_str(&format!(
+                " {}{}{} {}",
+                " ".repeat(left),
+                render_spans(spans),
+                " ".repeat(right),
+                bar
+            ));
+        }
+        out.push(line);
+        if has_header && r == 0 && cells.len() > 1 {
+            out.push(border("├", "┼", "┤"));
+        }
+    }
+    out.push(border("└", "┴", "┘"));
+    out
+}
+
+struct CodeBlock {
+    fence: String,
+    lang: String,
+}
+
+fn fence_open(line: &str) -> Option<CodeBlock> {
+    let marker = if line.starts_with("```") { '`' } else if line.starts_with("~~~") { '~' } else { return None };
+    let fence: String = line.chars().take_while(|&c| c == marker).collect();
+    let lang = line[fence.len()..].split_whitespace().next().unwrap_or("").to_string();
+    Some(CodeBlock { fence, lang })
+}
+
+// 块级渲染：逐行输入完整的 markdown 行，输出渲染后的终端行
+#[derive(Default)]
+pub struct BlockRenderer {
+    code: Option<CodeBlock>,
+    table: Vec<String>,
+}
+
+impl BlockRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 表格需要整体对齐，渲染前会先缓冲
+    pub fn in_table(&self) -> bool {
+        !self.table.is_empty()
+    }
+
+    pub fn line(&mut self, line: &str, width: usize) -> Vec<String> {
+        let mut out = Vec::new();
+        let trimmed = line.trim_start();
+
+        if let Some(code) = &self.code {
+            if trimmed.starts_with(&code.fence) && trimmed.trim_end().chars().all(|c| c == '`' || c == '~') {
+                out.push(format!("{}", "╰─".with(palette().border)));
+                self.code = None;
+            } else {
+                out.push(format!("{} {}", "│".with(palette().border), highlight_line(line, &code.lang)));
+            }
+            return out;
+        }
+
+        if trimmed.starts_with('|') {
+            self.table.push(line.to_string());
+            return out;
+        }
+        out.extend(self.flush_table());
+
+        if let Some(code) = fence_open(trimmed) {
+            out.push(format!("{}", format!("╭─ {}", code.lang).with(palette().border)));
+            self.code = Some(code);
+            return out;
+        }
+
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            let color = palette().heading;
+            let base = SpanStyle {
+                bold: true,
+                color: Some(color),
+                ..SpanStyle::default()
+            };
+            let spans = parse_inline(trimmed[level..].trim(), base);
+            out.extend(wrap(&spans, width, &Prefix::none()));
+            if level == 1 {
+                out.push(format!("{}", "═".repeat(spans_width(&spans).min(width)).with(color)));
+            }
+            return out;
+        }
+
+        let compact: String = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.len() >= 3
+            && ["-", "*", "_"].iter().any(|m| compact.chars().all(|c| c.to_string() == *m))
+        {
+            out.push(format!("{}", "─".repeat(width.min(80)).with(palette().border)));
+            return out;
+        }
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let bar = format!("{} ", "│".with(palette().border));
+            let prefix = Prefix {
+                first: bar.clone(),
+                rest: bar,
+                width: 2,
+            };
+            let base = SpanStyle {
+                italic: true,
+                ..SpanStyle::default()
+            };
+            out.extend(wrap(&parse_inline(quote.trim(), base), width, &prefix));
+            return out;
+        }
+
+        if let Some((marker, item)) = list_item(trimmed) {
+            let indent = " ".repeat(line.len() - trimmed.len());
+            let marker_width = marker.width();
+            let prefix = Prefix {
+                first: format!("{}{}", indent, marker.with(palette().accent)),
+                rest: " ".repeat(indent.len() + marker_width),
+                width: indent.len() + marker_width,
+            };
+            out.extend(wrap(&parse_inline(item, SpanStyle::default()), width, &prefix));
+            return out;
+        }
+
+        if trimmed.is_empty() {
+            out.push(String::new());
+            return out;
+        }
+
+        out.extend(wrap(&parse_inline(trimmed, SpanStyle::default()), width, &Prefix::none()));
+        out
+    }
+
+    // 输出结束时渲染缓冲的表格并闭合未结束的代码块
+    pub fn finish(&mut self) -> Vec<String> {
+        let mut out = self.flush_table();
+        if self.code.take().is_some() {
+            out.push(format!("{}", "╰─".with(palette().border)));
+        }
+        out
+    }
+
+    fn flush_table(&mut self) -> Vec<String> {
+        if self.table.is_empty() {
+            return Vec::new();
+        }
+        render_table(&std::mem::take(&mut self.table))
+    }
+}
+
+// 返回列表符号（无序列表换成 •，任务列表换成复选框）和内容
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            if let Some(task) = item.strip_prefix("[ ] ") {
+                return Some(("☐ ".to_string(), task));
+            }
+            if let Some(task) = item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] ")) {
+                return Some(("☑ ".to_string(), task));
+            }
+            return Some(("• ".to_string(), item));
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && digits <= 9 {
+        let rest = &line[digits..];
+        if let Some(item) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((format!("{}. ", &line[..digits]), item));
+        }
+    }
+    None
+}
+
+// 渲染完整的 markdown 文本
+pub fn render_markdown(text: &str, width: usize) -> Vec<String> {
+    let mut renderer = BlockRenderer::new();
+    let mut out = Vec::new();
+    for line in text.lines() {
+        out.extend(renderer.line(line, width));
+    }
+    out.extend(renderer.finish());
+    out
+}
+
+pub fn terminal_width() -> usize {
+    match terminal::size() {
+        Ok((w, _)) if w > 0 => w as usize,
+        _ => 80,
+    }
+}
+
+// 文本原样输出时在终端中占用的行数
+fn rows_for(text: &str, width: usize) -> usize {
+    text.width().div_ceil(width.max(1)).max(1)
+}
+
+// 流式输出：未完成的一行先原样输出，换行后擦除并重绘为渲染结果；
+// 每行渲染时重新读取终端宽度，调整窗口大小后新的内容按新宽度折行；
+// 已输出的回复由 /last 从保存的 markdown 按当前宽度重新渲染
+pub struct StreamRenderer {
+    blocks: BlockRenderer,
+    pending: String,
+    // 已原样输出、等待整体渲染的表格行占用的终端行数
+    table_rows: usize,
+    raw: bool,
+    ends_with_newline: bool,
+}
+
+impl StreamRenderer {
+    // 指定 raw 或不输出样式（不是终端、NO_COLOR 等）时原样输出
+    pub fn new(raw: bool) -> Self {
+        StreamRenderer {
+            blocks: BlockRenderer::new(),
+            pending: String::new(),
+            table_rows: 0,
+            raw: raw || !stdout_styled(),
+            ends_with_newline: true,
+        }
+    }
+
+    pub fn push(&mut self, chunk: &str) -> io::Result<()> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        let mut out = io::stdout().lock();
+        self.ends_with_newline = chunk.ends_with('\n');
+        if self.raw {
+            write!(out, "{}", chunk)?;
+            return out.flush();
+        }
+
+        let width = terminal_width();
+        for piece in chunk.split_inclusive('\n') {
+            match piece.strip_suffix('\n') {
+                Some(end) => {
+                    self.erase_pending(&mut out, width)?;
+                    let line = std::mem::take(&mut self.pending) + end;
+                    self.emit_line(&mut out, &line, width)?;
+                }
+                None => {
+                    self.pending.push_str(piece);
+                    write!(out, "{}", piece)?;
+                }
+            }
+        }
+        out.flush()
+    }
+
+    pub fn finish(&mut self) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        if self.raw {
+            if !self.ends_with_newline {
+                writeln!(out)?;
+            }
+            return out.flush();
+        }
+
+        let width = terminal_width();
+        if !self.pending.is_empty() {
+            self.erase_pending(&mut out, width)?;
+            let line = std::mem::take(&mut self.pending);
+            self.emit_line(&mut out, &line, width)?;
+        }
+        let was_table = self.blocks.in_table();
+        let lines = self.blocks.finish();
+        self.write_lines(&mut out, was_table, &lines)?;
+        out.flush()
+    }
+
+    fn emit_line(&mut self, out: &mut impl Write, line: &str, width: usize) -> io::Result<()> {
+        let was_table = self.blocks.in_table();
+        let lines = self.blocks.line(line, width);
+        if self.blocks.in_table() && lines.is_empty() {
+            // 表格行先原样显示，表格结束后整体重绘
+            writeln!(out, "{}", line)?;
+            self.table_rows += rows_for(line, width);
+            return Ok(());
+        }
+        self.write_lines(out, was_table, &lines)
+    }
+
+    fn write_lines(&mut self, out: &mut impl Write, was_table: bool, lines: &[String]) -> io::Result<()> {
+        if was_table && !self.blocks.in_table() && self.table_rows > 0 {
+            queue!(out, MoveToColumn(0), MoveUp(self.table_rows as u16), Clear(ClearType::FromCursorDown))?;
+            self.table_rows = 0;
+        }
+        for line in lines {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    fn erase_pending(&self, out: &mut impl Write, width: usize) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let rows = rows_for(&self.pending, width);
+        queue!(out, MoveToColumn(0))?;
+        if rows > 1 {
+            queue!(out, MoveUp((rows - 1) as u16))?;
+        }
+        queue!(out, Clear(ClearType::FromCursorDown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_markdown_rewraps_stored_reply_at_new_width() {
+        let reply = "这是一段保存在会话中的回复，".repeat(8);
+        let wide = render_markdown(&reply, 120);
+        let narrow = render_markdown(&reply, 40);
+        assert!(narrow.len() > wide.len());
+        assert!(narrow.iter().all(|line| line.width() <= 40));
+    }
+}
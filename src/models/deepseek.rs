@@ -4,9 +4,13 @@ use reqwest::header;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
+use std::io;
 
 
-use crate::{session::{manager::SessionManager, message::Message}};
+use crate::i18n::t;
+use crate::markdown::render::StreamRenderer;
+use crate::session::theme::notice;
+use crate::session::{config::Model, manager::SessionManager, message::Message};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct EventSteamDataChoice {
@@ -14,63 +18,78 @@ struct EventSteamDataChoice {
 }
 #[derive(Debug, Serialize, Deserialize)]
 struct EventSteamDataDelta {
-    content: String,
+    #[serde(default)]
+    content: Option<String>,
 }
 #[derive(Debug, Serialize, Deserialize)]
 struct EventSteamData {
     choices: Vec<EventSteamDataChoice>,
+    // 开启 include_usage 后最后一个数据块只包含用量
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+// 一次请求的 token 用量
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct PromptTokensDetails {
-    cached_tokens: u32,
+struct StreamOptions {
+    include_usage: bool,
 }
 
+// 非流式回复，只解析用到的字段
 #[derive(Debug, Serialize, Deserialize)]
 struct ChatCompletion {
-    id: String,
-    object: String,
-    created: u64,
-    model: String,
     choices: Vec<Choice>,
-    usage: Usage,
-    system_fingerprint: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Choice {
-    index: u32,
-    message: Message,
-    logprobs: Option<()>,
-    finish_reason: String,
+    message: ChatMessage,
 }
 
+// 发送给接口的消息只包含角色和内容
 #[derive(Debug, Serialize, Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
-    prompt_tokens_details: Option<PromptTokensDetails>,
-    prompt_cache_hit_tokens: u32,
-    prompt_cache_miss_tokens: u32,
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ResponseFormat {
-    r#type: String,
+impl From<&Message> for ChatMessage {
+    fn from(message: &Message) -> Self {
+        ChatMessage {
+            role: message.role.clone(),
+            content: message.content.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct RequestBody {
-    messages: Vec<Message>,
+    messages: Vec<ChatMessage>,
     model: String,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
 }
 
 #[derive(Debug)]
 pub enum AlterAIError {
     RequestFailed(reqwest::Error),
     InvalidResponse(String),
+    OutputFailed(io::Error),
+    ApiKey(String),
 }
 
 impl fmt::Display for AlterAIError {
@@ -78,6 +97,8 @@ impl fmt::Display for AlterAIError {
         match self {
             AlterAIError::RequestFailed(err) => write!(f, "Request failed: {}", err),
             AlterAIError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            AlterAIError::OutputFailed(err) => write!(f, "Output failed: {}", err),
+            AlterAIError::ApiKey(msg) => write!(f, "API key unavailable: {}", msg),
         }
     }
 }
@@ -90,36 +111,133 @@ impl From<serde_json::Error> for AlterAIError {
     }
 }
 
-pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(), AlterAIError> {
-    let client: reqwest::Client = reqwest::Client::new();
-
+fn build_headers(model: &Model, accept: &'static str) -> Result<header::HeaderMap, AlterAIError> {
     let mut headers = header::HeaderMap::new();
 
-    let auth = format!("Bearer {}", session_manager.config.default_model.api_key);
+    // key 可能来自环境变量、文件或命令，发送请求时才读取
+    let api_key = model.resolve_api_key().map_err(|e| AlterAIError::ApiKey(format!("{:#}", e)))?;
+    let auth = format!("Bearer {}", api_key);
 
     headers.insert(
         header::AUTHORIZATION,
-        header::HeaderValue::from_str(&auth.as_str()).unwrap(),
+        header::HeaderValue::from_str(auth.as_str())
+            .map_err(|e| AlterAIError::InvalidResponse(format!("Invalid api_key: {}", e)))?,
     );
     headers.insert(
         header::CONTENT_TYPE,
         header::HeaderValue::from_static("application/json"),
     );
-    headers.insert(
-        header::ACCEPT,
-        header::HeaderValue::from_static("text/event-stream"),
-    );
+    headers.insert(header::ACCEPT, header::HeaderValue::from_static(accept));
+    Ok(headers)
+}
+
+// 非流式请求，返回第一条回复的内容
+pub async fn deepseek_completion(
+    model: &Model,
+    messages: Vec<ChatMessage>,
+    max_tokens: Option<u32>,
+) -> Result<String, AlterAIError> {
+    let client: reqwest::Client = reqwest::Client::new();
+    let headers = build_headers(model, "application/json")?;
+
+    let question = RequestBody {
+        messages,
+        model: model.model.to_string(),
+        stream: false,
+        max_tokens,
+        temperature: None,
+        top_p: None,
+        stream_options: None,
+    };
+
+    let response = client
+        .post(model.api_url.as_str())
+        .headers(headers)
+        .json(&question)
+        .send()
+        .await
+        .map_err(AlterAIError::RequestFailed)?;
+    if !response.status().is_success() {
+        return Err(AlterAIError::InvalidResponse(format!(
+            "Request failed with status: {}",
+            response.status()
+        )));
+    }
+
+    let completion: ChatCompletion = response.json().await.map_err(AlterAIError::RequestFailed)?;
+    completion
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content)
+        .ok_or_else(|| AlterAIError::InvalidResponse("empty choices".to_string()))
+}
+
+// 在终端中流式渲染回复
+pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(), AlterAIError> {
+    let mut renderer = StreamRenderer::new(session_manager.raw_output);
+    let citations = deepseek_stream(session_manager, |delta| renderer.push(delta)).await?;
+    renderer.finish().map_err(AlterAIError::OutputFailed)?;
+    if !citations.is_empty() {
+        println!("\n{}", notice(t!("reply.citations", citations.join(", "))));
+    }
+    Ok(())
+}
+
+// 发送当前分支并流式接收回复，每段内容交给 on_delta 输出；
+// on_delta 返回错误时中止请求，完成后回复写入会话，返回检索引用的片段
+pub async fn deepseek_stream<F>(
+    session_manager: &mut SessionManager,
+    mut on_delta: F,
+) -> Result<Vec<String>, AlterAIError>
+where
+    F: FnMut(&str) -> io::Result<()>,
+{
+    let client: reqwest::Client = reqwest::Client::new();
+
+    // 当前角色可以指定模型和采样参数
+    let persona = session_manager.current_persona().cloned();
+    let model = session_manager.current_model().clone();
+
+    let headers = build_headers(&model, "text/event-stream")?;
+    let mut context_prompts = session_manager.pinned_context_prompts(model.context_window());
+    let retrieved = session_manager.retrieve_context();
+    let citations = match retrieved {
+        Some((prompt, citations)) => {
+            context_prompts.push(prompt);
+            citations
+        }
+        None => Vec::new(),
+    };
 
     if let Some(session) = session_manager.get_current_session() {
 
+        // 固定的项目上下文放在开头的系统消息之后，不写入会话记录
+        let mut messages: Vec<ChatMessage> =
+            session.active_branch().into_iter().map(ChatMessage::from).collect();
+        let insert_at = messages.iter().take_while(|m| m.role == "system").count();
+        for (i, content) in context_prompts.into_iter().enumerate() {
+            messages.insert(
+                insert_at + i,
+                ChatMessage {
+                    role: "system".to_string(),
+                    content,
+                },
+            );
+        }
+
         let question = RequestBody {
-            messages: session.messages.clone(),
-            model: session_manager.config.default_model.model.to_string(),
+            messages,
+            model: model.model.to_string(),
             stream: true,
+            max_tokens: None,
+            temperature: persona.as_ref().and_then(|p| p.temperature),
+            top_p: persona.as_ref().and_then(|p| p.top_p),
+            stream_options: Some(StreamOptions { include_usage: true }),
         };
     
         let response = client
-            .post(session_manager.config.default_model.api_url.as_str())
+            .post(model.api_url.as_str())
             .headers(headers)
             .json(&question)
             .send()
@@ -128,6 +246,7 @@ pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(),
         if response.status().is_success() {
             let mut stream = response.bytes_stream();
             let mut content = String::new();
+            let mut usage = None;
             while let Some(chunk) = stream.next().await {
                 let chunk = chunk.map_err(AlterAIError::RequestFailed)?;
                 let chunk_str = String::from_utf8_lossy(&chunk);
@@ -141,14 +260,20 @@ pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(),
                         if json_str == "[DONE]" {
                             break;
                         }
-                        match serde_json::from_str::<EventSteamData>(&json_str) {
+                        match serde_json::from_str::<EventSteamData>(json_str) {
                             Ok(steam_text) => {
-                                content += &steam_text.choices[0].delta.content;
-                                print!("{}", steam_text.choices[0].delta.content);
+                                usage = steam_text.usage.or(usage);
+                                let delta = steam_text
+                                    .choices
+                                    .into_iter()
+                                    .next()
+                                    .and_then(|choice| choice.delta.content)
+                                    .unwrap_or_default();
+                                content += &delta;
+                                on_delta(&delta).map_err(AlterAIError::OutputFailed)?;
                             }
                             Err(err) => {
-                                eprintln!("Failed to parse chunk: {}", err);
-                                eprintln!("Problematic chunk: {}", line);
+                                session_manager.warn(format!("Failed to parse chunk: {} ({})", err, line));
                             }
                         }
                     } else if line.starts_with("[DONE]") {
@@ -156,10 +281,11 @@ pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(),
                     }
                 }
             }
+            session_manager.last_usage = usage;
             if let Some(session) = session_manager.get_current_session() {
                 session.add_message("assistant", &content);
             }
-            return Ok(());
+            return Ok(citations);
         } else {
             return Err(AlterAIError::InvalidResponse(format!(
                 "Request failed with status: {}",
@@ -167,7 +293,5 @@ pub async fn deepseek_client(session_manager: &mut SessionManager) -> Result<(),
             )));
         }
     }
-    Err(AlterAIError::InvalidResponse(format!(
-        "not found current_session",
-    )))
+    Err(AlterAIError::InvalidResponse("not found current_session".to_string()))
 }
@@ -1,6 +1,64 @@
-use crate::{models::deepseek::deepseek_client, session::manager::SessionManager};
+use std::io;
+
+use crate::{
+    i18n::t,
+    models::deepseek::{deepseek_client, deepseek_completion, deepseek_stream, ChatMessage},
+    session::manager::SessionManager,
+};
 
 pub async fn generate_response(session_manager: &mut SessionManager) -> Result<(), anyhow::Error> {
-   let _ = deepseek_client(session_manager).await?;
+   deepseek_client(session_manager).await?;
+
+   // 标题生成失败时保留本地生成的临时标题
+   let _ = generate_title(session_manager).await;
+   Ok(())
+}
+
+// 回复交给 on_delta 输出而不是打印到终端，供全屏界面使用；返回检索引用的片段
+pub async fn generate_response_with<F>(
+   session_manager: &mut SessionManager,
+   on_delta: F,
+) -> Result<Vec<String>, anyhow::Error>
+where
+   F: FnMut(&str) -> io::Result<()>,
+{
+   let citations = deepseek_stream(session_manager, on_delta).await?;
+   let _ = generate_title(session_manager).await;
+   Ok(citations)
+}
+
+// 第一轮对话后请模型生成简短标题
+pub async fn generate_title(session_manager: &mut SessionManager) -> Result<(), anyhow::Error> {
+   let Some(session) = session_manager.get_current_session() else {
+      return Ok(());
+   };
+   if !session.needs_generated_title() {
+      return Ok(());
+   }
+
+   let conversation: String = session
+      .active_branch()
+      .iter()
+      .filter(|m| m.role != "system")
+      .map(|m| format!("{}: {}", m.role, m.content.chars().take(500).collect::<String>()))
+      .collect::<Vec<_>>()
+      .join("\n");
+   let messages = vec![
+      ChatMessage {
+         role: "system".to_string(),
+         content: t!("title.prompt").to_string(),
+      },
+      ChatMessage {
+         role: "user".to_string(),
+         content: conversation,
+      },
+   ];
+
+   // 与对话使用同一个模型（会话或角色指定的模型优先）
+   let model = session_manager.current_model().clone();
+   let title = deepseek_completion(&model, messages, Some(20)).await?;
+   if let Some(session) = session_manager.get_current_session() {
+      session.set_generated_title(&title);
+   }
    Ok(())
 }
\ No newline at end of file
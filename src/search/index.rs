@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+
+use crate::search::tokenizer::index_terms;
+use crate::session::manager::{Session, SessionError};
+use crate::session::message::Message;
+
+// 倒排索引：词项 -> 包含该词项的消息（"会话ID/消息ID"）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    postings: HashMap<String, HashSet<String>>,
+    sessions: HashMap<String, IndexedSession>,
+}
+
+// 记录每个会话索引时的状态，用于增量更新
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedSession {
+    fingerprint: String,
+    terms: HashSet<String>,
+}
+
+pub fn doc_key(session_id: &str, message_id: &str) -> String {
+    format!("{}/{}", session_id, message_id)
+}
+
+// 多段数据的 FNV-1a 哈希，用于写入文件的指纹，不能使用每个版本可能不同的 DefaultHasher；
+// 每段之后加入分隔符，避免 "ab"+"c" 与 "a"+"bc" 相同
+pub fn fnv1a<B: AsRef<[u8]>>(parts: impl IntoIterator<Item = B>) -> u64 {
+    parts.into_iter().fold(0xcbf29ce484222325u64, |hash, part| {
+        part.as_ref()
+            .iter()
+            .copied()
+            .chain([0xff])
+            .fold(hash, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    })
+}
+
+// 系统提示词不进入索引，只有搜索 role:system 时才逐条查找
+pub fn is_indexed(message: &Message) -> bool {
+    message.role != "system"
+}
+
+// 会话标题和已索引消息内容的哈希：消息数量不变的修改（编辑消息等）也会触发重建
+fn fingerprint(session: &Session) -> String {
+    let messages: Vec<&Message> = session.messages.iter().filter(|m| is_indexed(m)).collect();
+    let parts = std::iter::once(session.title.as_str())
+        .chain(messages.iter().flat_map(|m| [m.id.as_str(), m.content.as_str()]));
+    format!("{}:{:016x}", messages.len(), fnv1a(parts))
+}
+
+impl SearchIndex {
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        if !path.exists() {
+            return Ok(SearchIndex::default());
+        }
+        let index_file = File::open(path)?;
+        Ok(serde_json::from_reader(index_file)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        let index_file = File::create(path)?;
+        serde_json::to_writer(index_file, self)?;
+        Ok(())
+    }
+
+    // 只重建有变化的会话，并移除已不存在的会话，返回索引是否发生变化
+    pub fn update(&mut self, sessions: &HashMap<String, Session>) -> bool {
+        let stale: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(id, indexed)| {
+                sessions
+                    .get(*id)
+                    .is_none_or(|s| fingerprint(s) != indexed.fingerprint)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &stale {
+            self.remove_session(id);
+        }
+
+        let mut changed = !stale.is_empty();
+        for session in sessions.values() {
+            if !self.sessions.contains_key(&session.id) {
+                self.add_session(session);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn add_session(&mut self, session: &Session) {
+        let mut session_terms = HashSet::new();
+        for message in session.messages.iter().filter(|m| is_indexed(m)) {
+            let key = doc_key(&session.id, &message.id);
+            for term in index_terms(&message.content) {
+                self.postings.entry(term.clone()).or_default().insert(key.clone());
+                session_terms.insert(term);
+            }
+        }
+        self.sessions.insert(
+            session.id.clone(),
+            IndexedSession {
+                fingerprint: fingerprint(session),
+                terms: session_terms,
+            },
+        );
+    }
+
+    fn remove_session(&mut self, session_id: &str) {
+        let Some(indexed) = self.sessions.remove(session_id) else {
+            return;
+        };
+        let prefix = format!("{}/", session_id);
+        for term in indexed.terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.retain(|d| !d.starts_with(&prefix));
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    // 同时包含所有词项的消息；没有词项时返回 None，表示不做限制
+    pub fn candidates(&self, terms: &[String]) -> Option<HashSet<String>> {
+        let mut result: Option<HashSet<String>> = None;
+        for term in terms {
+            let docs = self.postings.get(term).cloned().unwrap_or_default();
+            result = Some(match result {
+                Some(current) => current.intersection(&docs).cloned().collect(),
+                None => docs,
+            });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(system_prompt: &str, question: &str) -> Session {
+        let mut session = Session::new(Some("测试"), system_prompt, None);
+        session.add_message("user", question);
+        session
+    }
+
+    fn sessions(list: Vec<Session>) -> HashMap<String, Session> {
+        list.into_iter().map(|s| (s.id.clone(), s)).collect()
+    }
+
+    fn found(index: &SearchIndex, term: &str) -> usize {
+        index.candidates(&[term.to_string()]).map_or(0, |c| c.len())
+    }
+
+    #[test]
+    fn update_reports_changes_only_once() {
+        let mut map = sessions(vec![session("", "rust 索引")]);
+        let mut index = SearchIndex::default();
+        assert!(index.update(&map));
+        assert!(!index.update(&map));
+        assert_eq!(found(&index, "rust"), 1);
+
+        map.insert("other".to_string(), session("", "rust 搜索"));
+        assert!(index.update(&map));
+        assert_eq!(found(&index, "rust"), 2);
+    }
+
+    #[test]
+    fn update_reindexes_edited_sessions() {
+        let mut map = sessions(vec![session("", "rust")]);
+        let mut index = SearchIndex::default();
+        index.update(&map);
+
+        let edited = map.values_mut().next().unwrap();
+        edited.messages.last_mut().unwrap().content = "python".to_string();
+        assert!(index.update(&map));
+        assert_eq!(found(&index, "rust"), 0);
+        assert_eq!(found(&index, "python"), 1);
+    }
+
+    #[test]
+    fn update_removes_deleted_sessions() {
+        let mut map = sessions(vec![session("", "rust"), session("", "rust python")]);
+        let mut index = SearchIndex::default();
+        index.update(&map);
+
+        let id = map.values().find(|s| s.messages[1].content == "rust python").unwrap().id.clone();
+        map.remove(&id);
+        assert!(index.update(&map));
+        assert_eq!(found(&index, "rust"), 1);
+        assert_eq!(found(&index, "python"), 0);
+        assert!(!index.postings.contains_key("python"));
+    }
+
+    #[test]
+    fn system_prompts_are_not_indexed() {
+        let map = sessions(vec![session("你是 rust 助手", "python")]);
+        let mut index = SearchIndex::default();
+        index.update(&map);
+        assert_eq!(found(&index, "rust"), 0);
+        assert_eq!(found(&index, "python"), 1);
+    }
+}
@@ -0,0 +1,276 @@
+pub mod index;
+pub mod tokenizer;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use crossterm::style::Stylize;
+
+use crate::i18n::t;
+use crate::search::index::{doc_key, is_indexed};
+use crate::search::tokenizer::query_terms;
+use crate::session::manager::SessionManager;
+use crate::session::theme::{palette, stdout_styled};
+
+// 搜索条件，支持 "短语"、role:user、after:2024-01-01、before:2024-12-31
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub keywords: Vec<String>,
+    pub phrases: Vec<String>,
+    pub role: Option<String>,
+    pub after: Option<NaiveDate>,
+    // 包含当天
+    pub before: Option<NaiveDate>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut query = SearchQuery::default();
+        let mut rest = input.trim();
+
+        while !rest.is_empty() {
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                let phrase = quoted[..end].trim();
+                if !phrase.is_empty() {
+                    query.phrases.push(phrase.to_string());
+                }
+                rest = quoted.get(end + 1..).unwrap_or("").trim_start();
+                continue;
+            }
+
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = rest[end..].trim_start();
+
+            if let Some(role) = token.strip_prefix("role:") {
+                query.role = Some(role.to_string());
+            } else if let Some(date) = token.strip_prefix("after:") {
+                query.after = Some(parse_date(date)?);
+            } else if let Some(date) = token.strip_prefix("before:") {
+                query.before = Some(parse_date(date)?);
+            } else {
+                query.keywords.push(token.to_string());
+            }
+        }
+
+        if query.keywords.is_empty() && query.phrases.is_empty() {
+            bail!(t!("search.empty_query"));
+        }
+        Ok(query)
+    }
+
+    // 需要在消息中出现并高亮的文本
+    pub fn needles(&self) -> Vec<String> {
+        self.phrases
+            .iter()
+            .chain(self.keywords.iter())
+            .map(|s| s.to_lowercase())
+            .collect()
+    }
+
+    fn matches_time(&self, timestamp: DateTime<Utc>) -> bool {
+        let date = timestamp.date_naive();
+        self.after.is_none_or(|after| date >= after)
+            && self.before.is_none_or(|before| date <= before)
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| t!("search.invalid_date", date))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_title: String,
+    pub role: String,
+    pub timestamp: DateTime<Utc>,
+    pub snippet: String,
+    // 高亮匹配后的片段，在终端中显示
+    #[serde(skip)]
+    pub highlighted: String,
+    pub matches: usize,
+}
+
+pub fn search(session_manager: &SessionManager, query: &SearchQuery, limit: usize) -> Result<Vec<SearchHit>> {
+    let index = session_manager
+        .refresh_search_index()
+        .context(t!("search.index_failed"))?;
+
+    let terms: Vec<String> = query
+        .phrases
+        .iter()
+        .chain(query.keywords.iter())
+        .flat_map(|s| query_terms(s))
+        .collect();
+    let include_system = query.role.as_deref() == Some("system");
+    let candidates = if include_system { None } else { index.candidates(&terms) };
+    let needles = query.needles();
+
+    let mut hits = Vec::new();
+    for session in session_manager.sessions.values() {
+        for message in &session.messages {
+            if !include_system && !is_indexed(message) {
+                continue;
+            }
+            if let Some(candidates) = &candidates
+                && !candidates.contains(&doc_key(&session.id, &message.id))
+            {
+                continue;
+            }
+            if query.role.as_ref().is_some_and(|role| role != &message.role)
+                || !query.matches_time(message.timestamp)
+            {
+                continue;
+            }
+
+            // 索引只做初筛，这里按原文确认每个关键词和短语都出现
+            let content = message.content.to_lowercase();
+            if !needles.iter().all(|n| content.contains(n.as_str())) {
+                continue;
+            }
+
+            let (snippet, highlighted, matches) = highlight_snippet(&message.content, &needles, 40);
+            hits.push(SearchHit {
+                session_id: session.id.clone(),
+                session_title: session.title.clone(),
+                role: message.role.clone(),
+                timestamp: message.timestamp,
+                snippet,
+                highlighted,
+                matches,
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| b.matches.cmp(&a.matches).then(b.timestamp.cmp(&a.timestamp)));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+// 截取第一个匹配附近的文本并高亮所有匹配，返回原文片段、高亮后的片段和匹配次数
+pub fn highlight_snippet(content: &str, needles: &[String], radius: usize) -> (String, String, usize) {
+    let chars: Vec<char> = content
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    let lower: Vec<char> = chars.iter().map(|c| lower_char(*c)).collect();
+
+    let mut highlighted = vec![false; chars.len()];
+    let mut first: Option<usize> = None;
+    let mut matches = 0;
+    for needle in needles {
+        let needle: Vec<char> = needle.chars().map(lower_char).collect();
+        if needle.is_empty() || needle.len() > lower.len() {
+            continue;
+        }
+        let mut i = 0;
+        while i + needle.len() <= lower.len() {
+            if lower[i..i + needle.len()] == needle[..] {
+                highlighted[i..i + needle.len()].fill(true);
+                first = Some(first.map_or(i, |f| f.min(i)));
+                matches += 1;
+                i += needle.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    let center = first.unwrap_or(0);
+    let start = center.saturating_sub(radius);
+    let end = (center + radius).min(chars.len());
+
+    let mut snippet = String::new();
+    let mut highlighted_snippet = String::new();
+    if start > 0 {
+        snippet.push_str("...");
+        highlighted_snippet.push_str("...");
+    }
+    let mut i = start;
+    while i < end {
+        let mark = highlighted[i];
+        let mut j = i;
+        while j < end && highlighted[j] == mark {
+            j += 1;
+        }
+        let segment: String = chars[i..j].iter().collect();
+        if mark {
+            if stdout_styled() {
+                highlighted_snippet.push_str(&segment.as_str().with(palette().accent).bold().to_string());
+            } else {
+                highlighted_snippet.push_str(&segment);
+            }
+        } else {
+            highlighted_snippet.push_str(&segment);
+        }
+        snippet.push_str(&segment);
+        i = j;
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+        highlighted_snippet.push_str("...");
+    }
+    (snippet, highlighted_snippet, matches)
+}
+
+fn lower_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::manager::testing::temp_manager;
+    use crate::session::manager::Session;
+
+    #[test]
+    fn parse_splits_phrases_keywords_and_filters() {
+        let query = SearchQuery::parse(r#"rust "错误 处理" role:user after:2024-01-01 before:2024-12-31 索引"#).unwrap();
+        assert_eq!(query.phrases, ["错误 处理"]);
+        assert_eq!(query.keywords, ["rust", "索引"]);
+        assert_eq!(query.role.as_deref(), Some("user"));
+        assert_eq!(query.after, NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(query.before, NaiveDate::from_ymd_opt(2024, 12, 31));
+    }
+
+    #[test]
+    fn parse_accepts_unclosed_phrase() {
+        let query = SearchQuery::parse(r#""未闭合 短语"#).unwrap();
+        assert_eq!(query.phrases, ["未闭合 短语"]);
+        assert!(query.keywords.is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_date_and_empty_query() {
+        assert!(SearchQuery::parse("rust after:2024-13-01").is_err());
+        assert!(SearchQuery::parse("role:user").is_err());
+        assert!(SearchQuery::parse(r#""""#).is_err());
+    }
+
+    #[test]
+    fn before_includes_the_whole_day() {
+        let query = SearchQuery::parse("rust after:2024-06-01 before:2024-06-01").unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(query.matches_time(day.and_hms_opt(23, 59, 59).unwrap().and_utc()));
+        assert!(!query.matches_time(day.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc()));
+    }
+
+    #[test]
+    fn system_prompts_match_only_with_role_system() {
+        let (mut manager, _dir) = temp_manager("search-system");
+        let mut session = Session::new(Some("测试"), "你是 rust 助手", None);
+        session.add_message("user", "rust 的所有权");
+        manager.sessions.insert(session.id.clone(), session);
+
+        let roles = |input: &str| -> Vec<String> {
+            let query = SearchQuery::parse(input).unwrap();
+            search(&manager, &query, 10).unwrap().into_iter().map(|h| h.role).collect()
+        };
+        assert_eq!(roles("rust"), ["user"]);
+        assert_eq!(roles("助手"), Vec::<String>::new());
+        assert_eq!(roles("助手 role:system"), ["system"]);
+    }
+}
@@ -0,0 +1,104 @@
+// 中日韩文字没有空格分词，按单字和相邻二字切分
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // 扩展A
+        | 0x4E00..=0x9FFF   // 基本汉字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // 兼容汉字
+        | 0x20000..=0x2FA1F)
+}
+
+// 索引时的词项：英文单词、中文单字以及二字组合
+pub fn index_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for run in split_runs(text) {
+        match run {
+            Run::Word(word) => terms.push(word),
+            Run::Cjk(chars) => {
+                terms.extend(chars.iter().map(|c| c.to_string()));
+                terms.extend(chars.windows(2).map(|w| w.iter().collect::<String>()));
+            }
+        }
+    }
+    terms
+}
+
+// 查询时的词项：中文只取二字组合，单字查询才使用单字
+pub fn query_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for run in split_runs(text) {
+        match run {
+            Run::Word(word) => terms.push(word),
+            Run::Cjk(chars) if chars.len() == 1 => terms.push(chars[0].to_string()),
+            Run::Cjk(chars) => {
+                terms.extend(chars.windows(2).map(|w| w.iter().collect::<String>()));
+            }
+        }
+    }
+    terms
+}
+
+enum Run {
+    Word(String),
+    Cjk(Vec<char>),
+}
+
+fn split_runs(text: &str) -> Vec<Run> {
+    let mut runs = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                runs.push(Run::Word(std::mem::take(&mut word)));
+            }
+            cjk.push(c);
+        } else if c.is_alphanumeric() || c == '_' {
+            if !cjk.is_empty() {
+                runs.push(Run::Cjk(std::mem::take(&mut cjk)));
+            }
+            word.extend(c.to_lowercase());
+        } else {
+            if !word.is_empty() {
+                runs.push(Run::Word(std::mem::take(&mut word)));
+            }
+            if !cjk.is_empty() {
+                runs.push(Run::Cjk(std::mem::take(&mut cjk)));
+            }
+        }
+    }
+    if !word.is_empty() {
+        runs.push(Run::Word(word));
+    }
+    if !cjk.is_empty() {
+        runs.push(Run::Cjk(cjk));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_terms_add_cjk_chars_and_bigrams() {
+        assert_eq!(index_terms("搜索会话"), ["搜", "索", "会", "话", "搜索", "索会", "会话"]);
+        assert_eq!(index_terms("字"), ["字"]);
+    }
+
+    #[test]
+    fn query_terms_use_bigrams_unless_single_char() {
+        assert_eq!(query_terms("搜索会话"), ["搜索", "索会", "会话"]);
+        assert_eq!(query_terms("字"), ["字"]);
+    }
+
+    #[test]
+    fn mixed_text_splits_at_script_and_punctuation() {
+        // 英文转为小写，标点分隔中文片段，二字组合不跨越片段
+        assert_eq!(query_terms("用Rust写，索引"), ["用", "rust", "写", "索引"]);
+        assert_eq!(index_terms("BM25检索"), ["bm25", "检", "索", "检索"]);
+        assert_eq!(index_terms("ひらがな 한국어"), ["ひ", "ら", "が", "な", "ひら", "らが", "がな", "한", "국", "어", "한국", "국어"]);
+    }
+}
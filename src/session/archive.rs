@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::Path;
+
+use crate::session::manager::{Session, SessionError};
+
+// 会话被归档的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveReason {
+    Retention,
+    Deleted,
+}
+
+// 归档文件中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub session: Session,
+    pub archived_at: DateTime<Utc>,
+    pub reason: ArchiveReason,
+}
+
+pub fn load_archive(path: &Path) -> Result<Vec<ArchivedSession>, SessionError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let archive_file = File::open(path)?;
+    Ok(serde_json::from_reader(archive_file)?)
+}
+
+pub fn save_archive(path: &Path, archive: &[ArchivedSession]) -> Result<(), SessionError> {
+    let archive_file = File::create(path)?;
+    serde_json::to_writer_pretty(archive_file, archive)?;
+    Ok(())
+}
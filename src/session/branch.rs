@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::session::manager::{Session, SessionError};
+use crate::session::message::Message;
+
+// head 为空字符串表示当前分支为空，下一条消息将成为新的根消息
+const EMPTY_BRANCH: &str = "";
+
+// 会话中的消息以 parent_id 组成一棵树，head 指向当前分支的末端消息
+impl Session {
+    pub fn head_id(&self) -> Option<&str> {
+        match self.head.as_deref() {
+            Some(EMPTY_BRANCH) => None,
+            Some(head) => Some(head),
+            // 旧版本保存的会话没有 head，取最后一条消息
+            None => self.messages.last().map(|m| m.id.as_str()),
+        }
+    }
+
+    // 旧版本的会话没有消息ID和 head，按顺序补齐为一条线性分支
+    pub fn ensure_message_ids(&mut self) {
+        let mut previous: Option<String> = None;
+        for message in &mut self.messages {
+            if message.id.is_empty() {
+                message.id = Uuid::new_v4().to_string();
+                message.parent_id = previous.clone();
+            }
+            previous = Some(message.id.clone());
+        }
+        if self.head.is_none() {
+            self.head = Some(previous.unwrap_or_else(|| EMPTY_BRANCH.to_string()));
+        }
+    }
+
+    // 从根消息到指定消息的路径
+    pub fn path_to(&self, message_id: &str) -> Vec<&Message> {
+        let by_id: HashMap<&str, &Message> =
+            self.messages.iter().map(|m| (m.id.as_str(), m)).collect();
+
+        let mut path = Vec::new();
+        let mut cursor = by_id.get(message_id).copied();
+        while let Some(message) = cursor {
+            // 防止损坏的数据形成环
+            if path.len() > self.messages.len() {
+                break;
+            }
+            path.push(message);
+            cursor = message
+                .parent_id
+                .as_deref()
+                .and_then(|id| by_id.get(id).copied());
+        }
+        path.reverse();
+        path
+    }
+
+    // 当前分支上的消息，发送给模型和导出时使用
+    pub fn active_branch(&self) -> Vec<&Message> {
+        match self.head_id() {
+            Some(head) => self.path_to(head),
+            None => Vec::new(),
+        }
+    }
+
+    // 所有分支的末端消息，按创建时间排序
+    pub fn leaves(&self) -> Vec<&Message> {
+        let parents: HashSet<&str> = self
+            .messages
+            .iter()
+            .filter_map(|m| m.parent_id.as_deref())
+            .collect();
+
+        let mut leaves: Vec<&Message> = self
+            .messages
+            .iter()
+            .filter(|m| !parents.contains(m.id.as_str()))
+            .collect();
+        leaves.sort_by_key(|m| m.timestamp);
+        leaves
+    }
+
+    // 将 head 移到当前分支的第 index 条消息（从1开始），下一条消息将开启新分支；
+    // 未指定时回到最后一条用户消息之前
+    pub fn fork_at(&mut self, index: Option<usize>) -> Result<usize, SessionError> {
+        let branch = self.active_branch();
+        let index = match index {
+            Some(index) => index,
+            None => branch
+                .iter()
+                .rposition(|m| m.role == "user")
+                .ok_or_else(|| SessionError::MessageNotFound("user".to_string()))?,
+        };
+
+        let target = index
+            .checked_sub(1)
+            .and_then(|i| branch.get(i))
+            .ok_or_else(|| SessionError::MessageNotFound(format!("#{}", index)))?;
+
+        self.head = Some(target.id.clone());
+        Ok(index)
+    }
+
+    // 当前分支上最后一条指定角色的消息
+    pub fn last_on_branch(&self, role: &str) -> Option<&Message> {
+        self.active_branch().into_iter().rev().find(|m| m.role == role)
+    }
+
+    // 将 head 退回到指定消息的父消息，被丢弃的消息保留在树中作为其他分支
+    pub fn rewind_before(&mut self, message_id: &str) -> Result<(), SessionError> {
+        let message = self
+            .messages
+            .iter()
+            .find(|m| m.id == message_id)
+            .ok_or_else(|| SessionError::MessageNotFound(message_id.to_string()))?;
+        self.head = Some(message.parent_id.clone().unwrap_or_else(|| EMPTY_BRANCH.to_string()));
+        Ok(())
+    }
+
+    // 同一父消息下相同角色的所有版本（包括自身），按创建时间排序
+    pub fn alternatives(&self, message_id: &str) -> Vec<&Message> {
+        let Some(message) = self.messages.iter().find(|m| m.id == message_id) else {
+            return Vec::new();
+        };
+        let mut alternatives: Vec<&Message> = self
+            .messages
+            .iter()
+            .filter(|m| m.parent_id == message.parent_id && m.role == message.role)
+            .collect();
+        alternatives.sort_by_key(|m| m.timestamp);
+        alternatives
+    }
+
+    pub fn checkout(&mut self, message_id: &str) -> Result<(), SessionError> {
+        if self.messages.iter().any(|m| m.id == message_id) {
+            self.head = Some(message_id.to_string());
+            Ok(())
+        } else {
+            Err(SessionError::MessageNotFound(message_id.to_string()))
+        }
+    }
+
+    // 只包含当前分支的会话副本，用于导出
+    pub fn active_branch_session(&self) -> Session {
+        let messages = self.active_branch().into_iter().cloned().collect();
+        Session {
+            messages,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 系统消息之后的一轮对话
+    fn session() -> Session {
+        let mut session = Session::new(None, "system", None);
+        session.add_message("user", "q1");
+        session.add_message("assistant", "a1");
+        session
+    }
+
+    fn contents(session: &Session) -> Vec<&str> {
+        session.active_branch().iter().map(|m| m.content.as_str()).collect()
+    }
+
+    fn id_of(session: &Session, content: &str) -> String {
+        session.messages.iter().find(|m| m.content == content).unwrap().id.clone()
+    }
+
+    #[test]
+    fn active_branch_follows_head() {
+        let mut session = session();
+        assert_eq!(contents(&session), ["system", "q1", "a1"]);
+
+        session.head = Some(id_of(&session, "q1"));
+        assert_eq!(contents(&session), ["system", "q1"]);
+    }
+
+    #[test]
+    fn fork_at_starts_a_sibling_branch() {
+        let mut session = session();
+        assert_eq!(session.fork_at(Some(2)).unwrap(), 2);
+        session.add_message("assistant", "a2");
+        assert_eq!(contents(&session), ["system", "q1", "a2"]);
+
+        let versions: Vec<&str> = session
+            .alternatives(&id_of(&session, "a2"))
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(versions, ["a1", "a2"]);
+
+        // 未指定位置时回到最后一条用户消息之前
+        assert_eq!(session.fork_at(None).unwrap(), 1);
+        assert_eq!(contents(&session), ["system"]);
+        assert!(session.fork_at(Some(0)).is_err());
+        assert!(session.fork_at(Some(9)).is_err());
+    }
+
+    #[test]
+    fn rewind_before_first_user_message_keeps_system_prompt() {
+        let mut session = session();
+        session.rewind_before(&id_of(&session, "q1")).unwrap();
+        assert_eq!(contents(&session), ["system"]);
+
+        session.add_message("user", "q2");
+        assert_eq!(contents(&session), ["system", "q2"]);
+        assert_eq!(session.alternatives(&id_of(&session, "q2")).len(), 2);
+    }
+
+    #[test]
+    fn rewind_before_root_message_empties_the_branch() {
+        let mut session = Session::new(None, "system", None);
+        session.rewind_before(&id_of(&session, "system")).unwrap();
+        session.add_message("user", "q1");
+        session.add_message("assistant", "a1");
+        assert_eq!(contents(&session), ["q1", "a1"]);
+
+        session.rewind_before(&id_of(&session, "q1")).unwrap();
+        assert_eq!(session.head_id(), None);
+        assert!(contents(&session).is_empty());
+
+        // 编辑后的消息成为新的根消息，而不是挂在旧回复下
+        session.add_message("user", "q1 edited");
+        assert_eq!(contents(&session), ["q1 edited"]);
+        assert_eq!(session.alternatives(&id_of(&session, "q1")).len(), 2);
+    }
+
+    #[test]
+    fn checkout_moves_head_to_existing_message() {
+        let mut session = session();
+        session.fork_at(Some(2)).unwrap();
+        session.add_message("assistant", "a2");
+
+        session.checkout(&id_of(&session, "a1")).unwrap();
+        assert_eq!(contents(&session), ["system", "q1", "a1"]);
+        assert!(matches!(session.checkout("missing"), Err(SessionError::MessageNotFound(_))));
+        assert_eq!(contents(&session), ["system", "q1", "a1"]);
+    }
+
+    #[test]
+    fn legacy_sessions_continue_from_last_message() {
+        let mut session = session();
+        for message in &mut session.messages {
+            message.id.clear();
+            message.parent_id = None;
+        }
+        session.head = None;
+        session.ensure_message_ids();
+        assert_eq!(contents(&session), ["system", "q1", "a1"]);
+
+        let mut empty = Session::new(None, "system", None);
+        empty.messages.clear();
+        empty.head = None;
+        empty.ensure_message_ids();
+        assert_eq!(empty.head_id(), None);
+    }
+}
@@ -1,31 +1,206 @@
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use crate::session::theme::Theme;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex};
+
+use crate::i18n::{t, Locale};
+use crate::session::theme::{CustomTheme, Theme};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub name: Option<String>,
     pub description: Option<String>,
     pub provider: Option<String>,
+    #[serde(default)]
     pub api_key: String,
+    // 以下任一项设置后不再使用 api_key，发送请求时才读取：
+    // 环境变量名、保存 key 的文件、输出 key 的命令（例如 pass show deepseek）
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    #[serde(default)]
+    pub api_key_file: Option<PathBuf>,
+    #[serde(default)]
+    pub api_key_cmd: Option<String>,
     pub api_url: String,
     pub api_version: Option<String>,
-    pub model: String
+    pub model: String,
+    // 模型的上下文窗口（token），用于限制附加的项目上下文
+    #[serde(default)]
+    pub context_window: Option<usize>,
+}
+
+pub const DEFAULT_CONTEXT_WINDOW: usize = 64_000;
+
+impl Model {
+    pub fn context_window(&self) -> usize {
+        self.context_window.unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    }
+
+    // 显示和选择模型时使用的名称，未设置 name 时使用 model
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.model)
+    }
+
+    // 依次使用 api_key_env、api_key_file、api_key_cmd，都未设置时使用 api_key；
+    // 文件和命令输出只取第一行
+    pub fn resolve_api_key(&self) -> Result<String> {
+        if let Some(name) = &self.api_key_env {
+            return env::var(name).map_err(|_| anyhow!(t!("config.key_env_missing", name)));
+        }
+        if let Some(path) = &self.api_key_file {
+            let path = expand_home(path);
+            let content = fs::read_to_string(&path)
+                .with_context(|| t!("config.key_file_failed", path.display()))?;
+            return Ok(first_line(&content));
+        }
+        if let Some(command) = &self.api_key_cmd {
+            let mut cache = COMMAND_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(key) = cache.get(command) {
+                return Ok(key.clone());
+            }
+            // 命令可能需要在终端中输入密码，只捕获标准输出
+            let output = if cfg!(target_os = "windows") {
+                Command::new("cmd").args(["/C", command]).stdin(Stdio::inherit()).stderr(Stdio::inherit()).output()
+            } else {
+                Command::new("sh").arg("-c").arg(command).stdin(Stdio::inherit()).stderr(Stdio::inherit()).output()
+            }
+            .with_context(|| t!("config.key_cmd_failed", command))?;
+            if !output.status.success() {
+                bail!(t!("config.key_cmd_status", command, output.status));
+            }
+            let key = first_line(&String::from_utf8_lossy(&output.stdout));
+            cache.insert(command.clone(), key.clone());
+            return Ok(key);
+        }
+        Ok(self.api_key.clone())
+    }
+
+    // 隐藏直接写在配置中的 key，用于显示模型
+    pub fn redacted(&self) -> Model {
+        let mut model = self.clone();
+        if !model.api_key.is_empty() {
+            model.api_key = redact(&model.api_key);
+        }
+        model
+    }
+
+    // 显示用的 key 来源，直接写在配置中的 key 只显示首尾几个字符
+    pub fn api_key_label(&self) -> String {
+        if let Some(name) = &self.api_key_env {
+            format!("env:{}", name)
+        } else if let Some(path) = &self.api_key_file {
+            format!("file:{}", path.display())
+        } else if let Some(command) = &self.api_key_cmd {
+            format!("cmd:{}", command)
+        } else if self.api_key.is_empty() {
+            t!("common.none").to_string()
+        } else {
+            redact(&self.api_key)
+        }
+    }
+}
+
+// api_key_cmd 的输出在进程内缓存，避免每次请求都运行命令
+static COMMAND_KEYS: LazyLock<Mutex<HashMap<String, String>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+// 支持 ~/ 开头的路径
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+// 只保留前 3 个和后 4 个字符，较短的 key 全部隐藏
+pub fn redact(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 12 {
+        return "****".to_string();
+    }
+    let head: String = chars[..3].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", head, tail)
 }
 
 
+// 角色：系统提示词、默认模型和采样参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    pub name: String,
+    pub system_prompt: String,
+    // 对应 Config.models 或 default_model 中的 name/model，为空时使用默认模型
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+}
+
+pub const DEFAULT_PERSONA: &str = "default";
+
+fn default_personas() -> Vec<Persona> {
+    vec![
+        Persona {
+            name: DEFAULT_PERSONA.to_string(),
+            system_prompt: t!("persona.default_prompt").to_string(),
+            model: None,
+            temperature: None,
+            top_p: None,
+        },
+        Persona {
+            name: "reviewer".to_string(),
+            system_prompt: t!("persona.reviewer_prompt").to_string(),
+            model: None,
+            temperature: Some(0.2),
+            top_p: None,
+        },
+    ]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub max_sessions: usize,
+    // 超过该天数未访问的会话会被归档
+    #[serde(default)]
+    pub max_session_age_days: Option<i64>,
+    // 所有会话序列化后的总大小上限（MB）
+    #[serde(default)]
+    pub max_sessions_size_mb: Option<u64>,
     pub auto_save: bool,
     pub default_model: Model,
     pub models: Option<Vec<Model>>,
     pub theme: Theme,
+    // 选择的自定义主题名称，设置后优先于 theme
+    #[serde(default)]
+    pub custom_theme: Option<String>,
+    #[serde(default)]
+    pub custom_themes: Vec<CustomTheme>,
+    #[serde(default = "default_personas")]
+    pub personas: Vec<Persona>,
+    // 新会话使用的角色
+    #[serde(default)]
+    pub default_persona: Option<String>,
+    // 界面语言，未设置时按 LANG 等环境变量选择
+    #[serde(default)]
+    pub language: Option<Locale>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             max_sessions: 100,
+            max_session_age_days: None,
+            max_sessions_size_mb: None,
             auto_save: true,
             default_model: Model {
                 name: Some("deepseek-chat".to_string()),
@@ -34,10 +209,248 @@ impl Default for Config {
                 description: Some("v3".to_string()),
                 model: "deepseek-chat".to_string(),
                 api_key: "".to_string(),
+                api_key_env: None,
+                api_key_file: None,
+                api_key_cmd: None,
                 api_url: "https://api.deepseek.com/chat/completions".to_string(),
+                context_window: Some(DEFAULT_CONTEXT_WINDOW),
             },
             models: None,
             theme: Theme::Dark,
+            custom_theme: None,
+            custom_themes: Vec::new(),
+            personas: default_personas(),
+            default_persona: None,
+            language: None,
         }
     }
-}
\ No newline at end of file
+}
+
+impl Config {
+    pub fn find_persona(&self, name: &str) -> Option<&Persona> {
+        self.personas.iter().find(|p| p.name == name)
+    }
+
+    // 按 name 或 model 查找模型
+    pub fn find_model(&self, name: &str) -> Option<&Model> {
+        self.all_models()
+            .find(|m| m.name.as_deref() == Some(name) || m.model == name)
+    }
+
+    // 默认模型和其他已配置的模型
+    pub fn all_models(&self) -> impl Iterator<Item = &Model> {
+        std::iter::once(&self.default_model).chain(self.models.iter().flatten())
+    }
+
+    // 当前主题的显示名称
+    pub fn theme_label(&self) -> String {
+        match &self.custom_theme {
+            Some(name) => name.clone(),
+            None => format!("{:?}", self.theme),
+        }
+    }
+
+    // 新会话使用的系统提示词
+    pub fn default_system_prompt(&self) -> String {
+        let name = self.default_persona.as_deref().unwrap_or(DEFAULT_PERSONA);
+        self.find_persona(name)
+            .map(|p| p.system_prompt.clone())
+            .unwrap_or_default()
+    }
+
+    // 按点号路径读取配置项，例如 default_model.api_url、models.0.name
+    pub fn get_value(&self, key: &str) -> Option<Value> {
+        let root = self.to_json().ok()?;
+        lookup_path(&root, key).cloned()
+    }
+
+    // 隐藏所有直接写在配置中的 key，用于显示配置
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        config.default_model = self.default_model.redacted();
+        config.models = self.models.as_ref().map(|models| models.iter().map(Model::redacted).collect());
+        config
+    }
+
+    // 经过文本转换，f32 字段保持配置文件中的写法（0.2 而不是 0.20000000298023224）
+    pub fn to_json(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(&serde_json::to_string(self)?)
+    }
+
+    // 修改点号路径对应的配置项，返回修改后的配置；
+    // 值先按 JSON 解析，解析失败或类型不符时作为字符串
+    pub fn set_value(&self, key: &str, raw: &str) -> Result<Config> {
+        let parsed = serde_json::from_str::<Value>(raw).ok();
+        if let Some(value) = parsed
+            && let Ok(config) = self.with_value(key, Some(value))
+        {
+            return Ok(config);
+        }
+        self.with_value(key, Some(Value::String(raw.to_string())))
+    }
+
+    // 删除点号路径对应的配置项；必填项恢复为默认值
+    pub fn unset_value(&self, key: &str) -> Result<Config> {
+        self.with_value(key, None).or_else(|_| {
+            let default = Config::default()
+                .get_value(key)
+                .ok_or_else(|| anyhow!(t!("config.unknown_key", key)))?;
+            self.with_value(key, Some(default))
+        })
+    }
+
+    fn with_value(&self, key: &str, value: Option<Value>) -> Result<Config> {
+        let mut root = self.to_json()?;
+        let (parent, last) = match key.rsplit_once('.') {
+            Some((parent, last)) => (lookup_path_mut(&mut root, parent), last),
+            None => (Some(&mut root), key),
+        };
+        let Some(parent) = parent else {
+            bail!(t!("config.unknown_key", key));
+        };
+        let setting = value.is_some();
+        match (parent, value) {
+            (Value::Object(map), Some(value)) => {
+                map.insert(last.to_string(), value);
+            }
+            (Value::Object(map), None) => {
+                if map.remove(last).is_none() {
+                    bail!(t!("config.unknown_key", key));
+                }
+            }
+            (Value::Array(items), value) => {
+                let index = last
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| *i < items.len())
+                    .ok_or_else(|| anyhow!(t!("config.unknown_key", key)))?;
+                match value {
+                    Some(value) => items[index] = value,
+                    None => {
+                        items.remove(index);
+                    }
+                }
+            }
+            _ => bail!(t!("config.unknown_key", key)),
+        }
+
+        let config: Config = serde_json::from_value(root)
+            .map_err(|e| anyhow!(t!("config.invalid_value", key, e)))?;
+        // 结构体会忽略未知字段，写回后读不到该键说明键不存在
+        if setting && config.get_value(key).is_none() {
+            bail!(t!("config.unknown_key", key));
+        }
+        Ok(config)
+    }
+}
+
+pub fn lookup_path<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(root, |value, part| match value {
+        Value::Object(map) => map.get(part),
+        Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+pub fn lookup_path_mut<'a>(root: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.').try_fold(root, |value, part| match value {
+        Value::Object(map) => map.get_mut(part),
+        Value::Array(items) => items.get_mut(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_value_follows_dotted_paths() {
+        let config = Config::default();
+        assert_eq!(config.get_value("max_sessions"), Some(json!(100)));
+        assert_eq!(config.get_value("default_model.model"), Some(json!("deepseek-chat")));
+        assert_eq!(config.get_value("personas.0.name"), Some(json!(config.personas[0].name)));
+        assert_eq!(config.get_value("max_session_age_days"), Some(Value::Null));
+        assert_eq!(config.get_value("personas.99"), None);
+        assert_eq!(config.get_value("no_such_key"), None);
+    }
+
+    #[test]
+    fn set_value_parses_json_and_falls_back_to_string() {
+        let config = Config::default();
+        assert_eq!(config.set_value("max_sessions", "5").unwrap().max_sessions, 5);
+        // 数字不符合字段类型时作为字符串
+        assert_eq!(config.set_value("default_model.model", "123").unwrap().default_model.model, "123");
+        let config = config.set_value("personas.0.temperature", "0.2").unwrap();
+        assert_eq!(config.get_value("personas.0.temperature"), Some(json!(0.2)));
+
+        assert!(config.set_value("max_sessions", "many").is_err());
+        assert!(config.set_value("no_such_key", "1").is_err());
+        assert!(config.set_value("personas.99.name", "x").is_err());
+    }
+
+    #[test]
+    fn unset_value_removes_optional_and_resets_required() {
+        let config = Config::default()
+            .set_value("max_session_age_days", "30")
+            .unwrap()
+            .set_value("max_sessions", "5")
+            .unwrap();
+        let config = config.unset_value("max_session_age_days").unwrap();
+        assert_eq!(config.max_session_age_days, None);
+        let config = config.unset_value("max_sessions").unwrap();
+        assert_eq!(config.max_sessions, Config::default().max_sessions);
+
+        let personas = config.personas.len();
+        assert_eq!(config.unset_value("personas.0").unwrap().personas.len(), personas - 1);
+        assert!(config.unset_value("no_such_key").is_err());
+    }
+
+    fn model_with_key(key: &str) -> Model {
+        let mut model = Config::default().default_model;
+        model.api_key = key.to_string();
+        model
+    }
+
+    #[test]
+    fn redact_hides_short_keys_and_keeps_ends_of_long_ones() {
+        assert_eq!(redact(""), "****");
+        assert_eq!(redact("sk-123456789"), "****");
+        assert_eq!(redact("sk-1234567890"), "sk-...7890");
+        // 按字符截取，多字节字符不会被截断
+        assert_eq!(redact("sk-密钥密钥密钥密钥密钥"), "sk-...密钥密钥");
+    }
+
+    #[test]
+    fn api_key_label_prefers_key_sources_over_inline_key() {
+        assert_eq!(model_with_key("sk-abcdefghijklmn").api_key_label(), "sk-...klmn");
+        assert_eq!(model_with_key("").api_key_label(), t!("common.none"));
+
+        let mut model = model_with_key("sk-abcdefghijklmn");
+        model.api_key_cmd = Some("pass show deepseek".to_string());
+        assert_eq!(model.api_key_label(), "cmd:pass show deepseek");
+        model.api_key_file = Some(PathBuf::from("~/.deepseek"));
+        assert_eq!(model.api_key_label(), "file:~/.deepseek");
+        model.api_key_env = Some("DEEPSEEK_API_KEY".to_string());
+        assert_eq!(model.api_key_label(), "env:DEEPSEEK_API_KEY");
+    }
+
+    #[test]
+    fn redacted_config_hides_every_inline_key() {
+        let config = Config {
+            default_model: model_with_key("sk-abcdefghijklmn"),
+            models: Some(vec![model_with_key("sk-zyxwvutsrqpon")]),
+            ..Config::default()
+        };
+        let shown = serde_json::to_string(&config.redacted()).unwrap();
+        assert!(!shown.contains("abcdefghijklmn"));
+        assert!(!shown.contains("zyxwvutsrqpon"));
+    }
+
+    #[test]
+    fn redacted_model_keeps_empty_key_empty() {
+        assert_eq!(model_with_key("sk-abcdefghijklmn").redacted().api_key, "sk-...klmn");
+        assert_eq!(model_with_key("").redacted().api_key, "");
+    }
+}
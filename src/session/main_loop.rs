@@ -1,11 +1,14 @@
-use std::io::{self, Write};
 use std::path::PathBuf;
 use std::error::Error;
 
 
 use crate::cli::alter::handle_command;
+use crate::cli::input::LineReader;
+use crate::i18n::t;
+use crate::context::files::{collect_attachments, compose_message, extract_mentions};
 use crate::models::model::generate_response;
 use crate::session::manager::SessionManager;
+use crate::session::theme::{notice, warning_label};
 
 pub async fn main_loop(
     session_manager: &mut SessionManager, 
@@ -13,40 +16,61 @@ pub async fn main_loop(
 ) -> Result<(), Box<dyn Error>> {
 
     let session_id = session_manager.current_session_id.as_ref()
-        .ok_or("没有当前会话")?.clone();
+        .ok_or(t!("error.no_current_session"))?.clone();
     
     let session_title = session_manager.sessions.get(&session_id)
         .map(|s| s.title.clone())
-        .unwrap_or_else(|| "未知会话".to_string());
+        .unwrap_or_else(|| t!("loop.unknown_session").to_string());
     
-    println!("会话: {} [ID: {}]", session_title, &session_id[..8]);
-    println!("输入 /help 查看可用命令");
+    println!("{}", notice(t!("loop.header", session_title, &session_id[..8])));
+    println!("{}", notice(t!("loop.help_hint")));
     
     // let mut last_save = Utc::now();
+    let mut reader = LineReader::new(session_manager.history_path.clone())?;
     
     loop {
-        print!("\n>: ");
-        std::io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        println!();
+        reader.refresh_completions(session_manager);
+        // Ctrl-D 与 /exit 相同
+        let Some(input) = reader.read_input(">: ")? else {
+            break;
+        };
         let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
         
-        if input.starts_with('/') {
-            if handle_command(&input[1..], session_manager, sessions_path)? {
+        if let Some(command) = input.strip_prefix('/') {
+            if handle_command(command, session_manager, sessions_path).await? {
                 break;
             }
             continue;
         }
         
+        // @路径 形式的附件和 /file 添加的附件一起发送
+        let (text, mentions) = extract_mentions(input);
+        let (attachments, warnings) = collect_attachments(&mentions);
+        for warning in warnings {
+            eprintln!("{} {}", warning_label(), warning);
+        }
+        let mut pending = std::mem::take(&mut session_manager.pending_attachments);
+        pending.extend(attachments);
+        for attachment in &pending {
+            println!("{}", notice(t!("loop.attachment", attachment.path.display())));
+        }
+        let content = compose_message(&text, &pending);
+
         if let Some(session) = session_manager.get_current_session() {
-            session.add_message("user", input);
+            session.add_message("user", &content);
         }
         
-        let _  = generate_response(session_manager).await?;
+        generate_response(session_manager).await?;
     }
     
     if session_manager.config.auto_save {
         session_manager.save_sessions(sessions_path)?;
+    } else {
+        session_manager.save_state()?;
     }
     
     Ok(())
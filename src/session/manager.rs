@@ -1,16 +1,26 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{self};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use chrono::prelude::*;
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
 
+use crate::context::files::{walk_files, Attachment};
+use crate::context::project::{fingerprint, render_context, RenderedContext};
+use crate::context::rag::{render_chunks, Bm25Index, RetrievalSettings};
 use crate::markdown::parser::FileParser;
-use crate::session::config::Config;
+use crate::models::deepseek::TokenUsage;
+use crate::search::index::SearchIndex;
+use crate::i18n::t;
+use crate::session::archive::{load_archive, save_archive, ArchiveReason, ArchivedSession};
+use crate::session::config::{Config, Model, Persona};
 use crate::session::message::Message;
+use crate::session::state::{SessionState, MAX_RECENT_SESSIONS};
+use crate::session::theme::warning_label;
 
 // 自定义错误类型
 #[derive(Debug)]
@@ -18,22 +28,53 @@ pub enum SessionError {
     IoError(io::Error),
     JsonError(serde_json::Error),
     SessionNotFound(String),
+    MessageNotFound(String),
+    PersonaNotFound(String),
+    ModelNotFound(String),
+    // 会话标识匹配到多个会话，附带候选列表
+    AmbiguousSession(String, Vec<String>),
     InvalidSessionId,
 }
 
 impl fmt::Display for SessionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            SessionError::IoError(e) => write!(f, "IO错误: {}", e),
-            SessionError::JsonError(e) => write!(f, "JSON错误: {}", e),
-            SessionError::SessionNotFound(id) => write!(f, "会话未找到: {}", id),
-            SessionError::InvalidSessionId => write!(f, "无效的会话ID"),
+            SessionError::IoError(e) => write!(f, "{}", t!("error.io", e)),
+            SessionError::JsonError(e) => write!(f, "{}", t!("error.json", e)),
+            SessionError::SessionNotFound(id) => write!(f, "{}", t!("error.session_not_found", id)),
+            SessionError::MessageNotFound(id) => write!(f, "{}", t!("error.message_not_found", id)),
+            SessionError::PersonaNotFound(name) => write!(f, "{}", t!("error.persona_not_found", name)),
+            SessionError::ModelNotFound(name) => write!(f, "{}", t!("error.model_not_found", name)),
+            SessionError::AmbiguousSession(query, candidates) => {
+                write!(f, "{}", t!("error.ambiguous_session", query))?;
+                for candidate in candidates {
+                    write!(f, "\n  {}", candidate)?;
+                }
+                Ok(())
+            }
+            SessionError::InvalidSessionId => write!(f, "{}", t!("error.invalid_session_id")),
         }
     }
 }
 
 impl Error for SessionError {}
 
+impl SessionError {
+    // 不随语言变化的错误代码，用于 --output json/plain
+    pub fn code(&self) -> &'static str {
+        match self {
+            SessionError::IoError(_) => "io",
+            SessionError::JsonError(_) => "json",
+            SessionError::SessionNotFound(_) => "session_not_found",
+            SessionError::MessageNotFound(_) => "message_not_found",
+            SessionError::PersonaNotFound(_) => "persona_not_found",
+            SessionError::ModelNotFound(_) => "model_not_found",
+            SessionError::AmbiguousSession(..) => "ambiguous_session",
+            SessionError::InvalidSessionId => "invalid_session_id",
+        }
+    }
+}
+
 impl From<io::Error> for SessionError {
     fn from(err: io::Error) -> SessionError {
         SessionError::IoError(err)
@@ -54,47 +95,181 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     pub last_accessed: DateTime<Utc>,
     pub messages: Vec<Message>,
+    // 当前分支末端消息的ID，为空时取最后一条消息
+    #[serde(default)]
+    pub head: Option<String>,
+    // 置顶的会话不受保留策略影响，并在列表中排在最前
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // 所属目录/项目，使用 / 分隔层级，例如 work/api
+    #[serde(default)]
+    pub folder: Option<String>,
+    // 用户手动设置过标题时不再自动生成
+    #[serde(default)]
+    pub custom_title: bool,
+    // 当前使用的角色，为空时使用默认模型和参数
+    #[serde(default)]
+    pub persona: Option<String>,
+    // 通过 /model 为当前会话指定的模型，优先于角色和默认模型
+    #[serde(default)]
+    pub model: Option<String>,
+    // 通过 /context add 固定的项目目录，每轮对话都会附带其上下文
+    #[serde(default)]
+    pub pinned_contexts: Vec<PathBuf>,
+    // 开启后每条用户消息都会从项目索引中检索相关片段
+    #[serde(default)]
+    pub retrieval: Option<RetrievalSettings>,
 }
 
 impl Session {
-    fn new(title: &str) -> Self {
+    pub fn new(title: Option<&str>, system_prompt: &str, persona: Option<String>) -> Self {
         let now = Utc::now();
+        let system = Message::new("system", system_prompt, None);
         Session {
             id: Uuid::new_v4().to_string(),
-            title: title.to_string(),
+            title: title.unwrap_or(t!("session.default_title")).to_string(),
             created_at: now,
             last_accessed: now,
-            messages: vec![Message {
-                role: "system".to_string(),
-                content: "现在你是一个心灵使者 ， 不管用户说什么，你都要是用户心灵舒畅".to_string(),
-                timestamp: now,
-            }]
+            head: Some(system.id.clone()),
+            messages: vec![system],
+            pinned: false,
+            tags: Vec::new(),
+            folder: None,
+            custom_title: title.is_some(),
+            persona,
+            model: None,
+            pinned_contexts: Vec::new(),
+            retrieval: None,
+        }
+    }
+
+    // 替换根系统消息，之后的请求都使用新的系统提示词
+    pub fn set_system_prompt(&mut self, system_prompt: &str) {
+        let mut replaced = false;
+        for message in &mut self.messages {
+            if message.parent_id.is_none() && message.role == "system" {
+                message.content = system_prompt.to_string();
+                replaced = true;
+            }
+        }
+        if !replaced {
+            let system = Message::new("system", system_prompt, None);
+            // 原有的根消息挂到新的系统消息下
+            for message in &mut self.messages {
+                if message.parent_id.is_none() {
+                    message.parent_id = Some(system.id.clone());
+                }
+            }
+            if self.head_id().is_none() {
+                self.head = Some(system.id.clone());
+            }
+            self.messages.insert(0, system);
         }
     }
     
+    // 消息追加到当前分支末端
     pub fn add_message(&mut self, role: &str, content: &str) {
-        self.messages.push(Message {
-            role: role.to_string(),
-            content: content.to_string(),
-            timestamp: Utc::now(),
-        });
+        // 未手动设置标题时，先用第一条用户消息作为临时标题，等待模型生成
+        if role == "user" && !self.custom_title && self.last_on_branch("user").is_none() {
+            self.title = title_preview(content, 20);
+        }
+
+        let parent_id = self.head_id().map(str::to_string);
+        let message = Message::new(role, content, parent_id);
+        self.head = Some(message.id.clone());
+        self.messages.push(message);
         self.last_accessed = Utc::now();
-        
-        // 如果标题为空，使用第一条用户消息作为标题
-        if self.title.is_empty() && role == "user" {
-            let preview = if content.len() > 20 {
-                format!("{}...", &content[..20])
-            } else {
-                content.to_string()
-            };
-            self.title = preview;
+    }
+
+    // 第一轮对话完成且标题未被用户设置时，需要由模型生成标题
+    pub fn needs_generated_title(&self) -> bool {
+        if self.custom_title {
+            return false;
+        }
+        let branch = self.active_branch();
+        branch.last().is_some_and(|m| m.role == "assistant")
+            && branch.iter().filter(|m| m.role == "user").count() == 1
+    }
+
+    // 模型生成的标题不影响 custom_title
+    pub fn set_generated_title(&mut self, title: &str) {
+        let title = title.trim().trim_matches(|c| c == '"' || c == '“' || c == '”' || c == '《' || c == '》');
+        if !title.is_empty() {
+            self.title = title_preview(title, 30);
         }
     }
     
+    // 会话序列化后的字节数，用于总大小限制
+    pub fn size_bytes(&self) -> u64 {
+        serde_json::to_vec(self).map(|v| v.len() as u64).unwrap_or(0)
+    }
+
     pub fn update_title(&mut self, title: &str) {
         self.title = title.to_string();
+        self.custom_title = true;
         self.last_accessed = Utc::now();
     }
+
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches('#');
+            if !tag.is_empty() && !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    pub fn remove_tags(&mut self, tags: &[String]) {
+        self.tags
+            .retain(|t| !tags.iter().any(|tag| tag.trim().trim_start_matches('#') == t));
+    }
+
+    pub fn set_folder(&mut self, folder: Option<&str>) {
+        self.folder = folder
+            .map(|f| f.trim().trim_matches('/').to_string())
+            .filter(|f| !f.is_empty());
+    }
+}
+
+// 按字符截断标题，避免在多字节字符中间切分
+fn title_preview(content: &str, max_chars: usize) -> String {
+    let first_line = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if first_line.chars().count() > max_chars {
+        format!("{}...", first_line.chars().take(max_chars).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+// 会话列表过滤条件
+#[derive(Debug, Default)]
+pub struct SessionFilter {
+    pub tag: Option<String>,
+    // 匹配该目录及其子目录
+    pub folder: Option<String>,
+    pub pinned: bool,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &Session) -> bool {
+        if self.pinned && !session.pinned {
+            return false;
+        }
+        if let Some(tag) = &self.tag
+            && !session.tags.iter().any(|t| t == tag.trim_start_matches('#'))
+        {
+            return false;
+        }
+        if let Some(folder) = &self.folder {
+            let folder = folder.trim_matches('/');
+            return session.folder.as_deref().is_some_and(|f| {
+                f == folder || f.starts_with(&format!("{}/", folder))
+            });
+        }
+        true
+    }
 }
 
 
@@ -102,8 +277,29 @@ impl Session {
 pub struct SessionManager {
     pub sessions: HashMap<String, Session>,
     pub current_session_id: Option<String>,
+    pub recent_sessions: Vec<String>,
     pub config: Config,
     pub config_path: PathBuf,
+    pub state_path: PathBuf,
+    pub archive_path: PathBuf,
+    pub search_index_path: PathBuf,
+    pub templates_path: PathBuf,
+    pub indexes_path: PathBuf,
+    pub history_path: PathBuf,
+    // 为 true 时回复原样输出，不渲染 markdown
+    pub raw_output: bool,
+    // 最近一次请求的 token 用量
+    pub last_usage: Option<TokenUsage>,
+    // 通过 /file 添加、随下一条用户消息发送的附件
+    pub pending_attachments: Vec<Attachment>,
+    // 已渲染的项目上下文，文件未变化时复用
+    pub context_cache: HashMap<PathBuf, RenderedContext>,
+    // 已加载的检索索引
+    pub index_cache: HashMap<PathBuf, Bm25Index>,
+    // 为 Some 时警告暂存在这里由调用方展示，不直接输出到标准错误（TUI 使用）
+    pub warnings: Option<Vec<String>>,
+    // 本次运行中移出会话列表的会话，与会话列表在 save_sessions 中一起写入
+    pending_archive: Vec<ArchivedSession>,
 }
 
 impl SessionManager {
@@ -121,35 +317,112 @@ impl SessionManager {
             serde_json::to_writer_pretty(config_file, &default_config)?;
         }
         
+        let state_path = config_path.with_file_name("state.json");
+        let archive_path = config_path.with_file_name("archive.json");
+        let search_index_path = config_path.with_file_name("search_index.json");
+        let templates_path = config_path.with_file_name("templates");
+        let indexes_path = config_path.with_file_name("indexes");
+        let history_path = config_path.with_file_name("history.txt");
+
         Ok(SessionManager {
             sessions: HashMap::new(),
             current_session_id: None,
+            recent_sessions: Vec::new(),
             config,
             config_path,
+            state_path,
+            archive_path,
+            search_index_path,
+            templates_path,
+            indexes_path,
+            history_path,
+            raw_output: false,
+            last_usage: None,
+            pending_attachments: Vec::new(),
+            warnings: None,
+            context_cache: HashMap::new(),
+            index_cache: HashMap::new(),
+            pending_archive: Vec::new(),
         })
     }
     
-    pub fn create_session(&mut self, title: &str) -> &str {
-        // 清理旧会话
-        if self.sessions.len() >= self.config.max_sessions {
+    // 未指定标题时使用默认标题，并在第一轮对话后由模型生成
+    pub fn create_session(&mut self, title: Option<&str>) -> &str {
+        let session = Session::new(
+            title,
+            &self.config.default_system_prompt(),
+            self.config.default_persona.clone(),
+        );
+        let id = session.id.clone();
+        self.sessions.insert(id.clone(), session);
+        self.set_current_session(&id);
+
+        // 新会话已计入总数并成为当前会话，清理时不会被移除
+        if self.sessions.len() > self.config.max_sessions {
             self.cleanup_old_sessions();
         }
-        
-        let session = Session::new(title);
+        &self.sessions[&id].id
+    }
+
+    // 单次提问使用的临时会话：不执行保留策略，不计入最近会话，调用方不保存时不留下记录
+    pub fn open_ephemeral_session(&mut self, title: &str) -> String {
+        let session = Session::new(
+            Some(title),
+            &self.config.default_system_prompt(),
+            self.config.default_persona.clone(),
+        );
         let id = session.id.clone();
         self.sessions.insert(id.clone(), session);
         self.current_session_id = Some(id.clone());
-        &self.sessions[&id].id
+        id
     }
     
     pub fn switch_session(&mut self, session_id: &str) -> Result<(), SessionError> {
         if self.sessions.contains_key(session_id) {
-            self.current_session_id = Some(session_id.to_string());
+            self.set_current_session(session_id);
             Ok(())
         } else {
             Err(SessionError::SessionNotFound(session_id.to_string()))
         }
     }
+
+    // 把用户输入的会话标识解析为完整ID：
+    // 完整ID、唯一的ID前缀、列表序号（#3）或完整标题
+    pub fn resolve_session(&self, query: &str) -> Result<String, SessionError> {
+        let sessions = self.list_sessions();
+        resolve_among(query, sessions.iter().map(|s| (s.id.as_str(), s.title.as_str())))
+    }
+
+    // 同上，在回收站/归档中查找，序号对应 trash list 的顺序
+    pub fn resolve_archived(&self, query: &str) -> Result<String, SessionError> {
+        let archived = self.list_archived()?;
+        resolve_among(
+            query,
+            archived.iter().map(|a| (a.session.id.as_str(), a.session.title.as_str())),
+        )
+    }
+
+    // 切换回上一个使用的会话
+    pub fn switch_to_previous(&mut self) -> Result<String, SessionError> {
+        let previous = self
+            .recent_sessions
+            .iter()
+            .find(|id| {
+                Some(*id) != self.current_session_id.as_ref() && self.sessions.contains_key(*id)
+            })
+            .cloned()
+            .ok_or_else(|| SessionError::SessionNotFound("-".to_string()))?;
+
+        self.set_current_session(&previous);
+        Ok(previous)
+    }
+
+    fn set_current_session(&mut self, session_id: &str) {
+        self.current_session_id = Some(session_id.to_string());
+        self.recent_sessions.retain(|id| id != session_id);
+        self.recent_sessions.insert(0, session_id.to_string());
+        self.recent_sessions.truncate(MAX_RECENT_SESSIONS);
+    }
     
     pub fn get_current_session(&mut self) -> Option<&mut Session> {
         if let Some(ref id) = self.current_session_id {
@@ -159,47 +432,270 @@ impl SessionManager {
         }
     }
     
+    pub fn current_persona(&self) -> Option<&Persona> {
+        let session = self.sessions.get(self.current_session_id.as_ref()?)?;
+        self.config.find_persona(session.persona.as_deref()?)
+    }
+
+    // 当前会话使用的模型：会话指定的模型，其次角色指定的模型，否则为默认模型
+    pub fn current_model(&self) -> &Model {
+        let session_model = self
+            .current_session_id
+            .as_ref()
+            .and_then(|id| self.sessions.get(id))
+            .and_then(|s| s.model.as_deref());
+        session_model
+            .or_else(|| self.current_persona().and_then(|p| p.model.as_deref()))
+            .and_then(|name| self.config.find_model(name))
+            .unwrap_or(&self.config.default_model)
+    }
+
+    // 为当前会话指定模型，None 时恢复使用角色或默认模型
+    pub fn set_session_model(&mut self, name: Option<&str>) -> Result<(), SessionError> {
+        if let Some(name) = name
+            && self.config.find_model(name).is_none()
+        {
+            return Err(SessionError::ModelNotFound(name.to_string()));
+        }
+        let session = self.get_current_session().ok_or(SessionError::InvalidSessionId)?;
+        session.model = name.map(str::to_string);
+        Ok(())
+    }
+
+    // 切换当前会话的角色
+    pub fn set_persona(&mut self, name: &str) -> Result<(), SessionError> {
+        let persona = self
+            .config
+            .find_persona(name)
+            .cloned()
+            .ok_or_else(|| SessionError::PersonaNotFound(name.to_string()))?;
+        let session = self.get_current_session().ok_or(SessionError::InvalidSessionId)?;
+        session.set_system_prompt(&persona.system_prompt);
+        session.persona = Some(persona.name);
+        Ok(())
+    }
+
+    // 输出警告；TUI 中收集起来显示在状态栏
+    pub fn warn(&mut self, message: impl Into<String>) {
+        match &mut self.warnings {
+            Some(warnings) => warnings.push(message.into()),
+            None => eprintln!("{} {}", warning_label(), message.into()),
+        }
+    }
+
+    // 当前会话固定的项目上下文，平分模型上下文窗口的一半
+    pub fn pinned_context_prompts(&mut self, context_window: usize) -> Vec<String> {
+        let roots = match self.get_current_session() {
+            Some(session) if !session.pinned_contexts.is_empty() => session.pinned_contexts.clone(),
+            _ => return Vec::new(),
+        };
+        let budget = context_window / 2 / roots.len();
+
+        let mut prompts = Vec::new();
+        for root in roots {
+            match self.rendered_context(&root, budget) {
+                Ok(rendered) => prompts.push(rendered.content.clone()),
+                Err(e) => self.warn(t!("manager.context_failed", root.display(), e)),
+            }
+        }
+        prompts
+    }
+
+    // 文件没有变化时复用缓存，否则重新生成
+    pub fn rendered_context(&mut self, root: &Path, budget: usize) -> anyhow::Result<&RenderedContext> {
+        let current = fingerprint(&walk_files(root));
+        let fresh = self
+            .context_cache
+            .get(root)
+            .is_some_and(|cached| cached.fingerprint == current && cached.budget == budget);
+        if !fresh {
+            let rendered = render_context(root, budget)?;
+            self.context_cache.insert(root.to_path_buf(), rendered);
+        }
+        Ok(&self.context_cache[root])
+    }
+
+    // 按最后一条用户消息检索相关片段，返回注入的上下文和引用位置
+    pub fn retrieve_context(&mut self) -> Option<(String, Vec<String>)> {
+        let session = self.get_current_session()?;
+        let settings = session.retrieval.clone()?;
+        let query = session.last_on_branch("user")?.content.clone();
+
+        if !self.index_cache.contains_key(&settings.root) {
+            match Bm25Index::load(&self.indexes_path, &settings.root) {
+                Ok(index) => {
+                    if index.is_stale() {
+                        self.warn(t!("manager.index_stale", settings.root.display()));
+                    }
+                    self.index_cache.insert(settings.root.clone(), index);
+                }
+                Err(e) => {
+                    self.warn(format!("{:#}", e));
+                    return None;
+                }
+            }
+        }
+
+        let index = &self.index_cache[&settings.root];
+        let chunks: Vec<_> = index
+            .search(&query, settings.top_k)
+            .into_iter()
+            .map(|(chunk, _)| chunk)
+            .collect();
+        if chunks.is_empty() {
+            return None;
+        }
+        let citations = chunks.iter().map(|c| c.citation()).collect();
+        Some((render_chunks(&chunks), citations))
+    }
+
     pub fn list_sessions(&self) -> Vec<&Session> {
-        let mut sessions: Vec<&Session> = self.sessions.values().collect();
+        self.filter_sessions(&SessionFilter::default())
+    }
+
+    pub fn filter_sessions(&self, filter: &SessionFilter) -> Vec<&Session> {
+        let mut sessions: Vec<&Session> =
+            self.sessions.values().filter(|s| filter.matches(s)).collect();
         
-        // 按最后访问时间排序，最近的在前
-        sessions.sort_by(|a, b| b.last_accessed.cmp(&a.last_accessed));
+        // 置顶的在前，其余按最后访问时间排序，最近的在前
+        sessions.sort_by_key(|s| (std::cmp::Reverse(s.pinned), std::cmp::Reverse(s.last_accessed)));
         
         sessions
     }
+
+    pub fn get_session_mut(&mut self, session_id: &str) -> Result<&mut Session, SessionError> {
+        self.sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))
+    }
     
-    pub fn cleanup_old_sessions(&mut self) {
-        if self.sessions.len() <= self.config.max_sessions {
-            return;
+    // 按保留策略计算需要移除的会话，最旧的在前；置顶会话和当前会话不会被选中
+    pub fn plan_cleanup(&self) -> Vec<&Session> {
+        let mut candidates: Vec<&Session> = self
+            .sessions
+            .values()
+            .filter(|s| !s.pinned && Some(&s.id) != self.current_session_id.as_ref())
+            .collect();
+        candidates.sort_by_key(|s| s.last_accessed);
+
+        let mut removed: HashSet<&str> = HashSet::new();
+
+        // 超过最大保留天数
+        if let Some(days) = self.config.max_session_age_days {
+            let cutoff = Utc::now() - Duration::days(days);
+            for session in &candidates {
+                if session.last_accessed < cutoff {
+                    removed.insert(&session.id);
+                }
+            }
         }
-        
-        let mut sessions_vec: Vec<&Session> = self.sessions.values().collect();
-        sessions_vec.sort_by(|a, b| a.last_accessed.cmp(&b.last_accessed));
-        
-        // 移除最旧的，直到满足最大会话数限制
-        for session in sessions_vec {
-            if self.sessions.len() <= self.config.max_sessions {
+
+        // 超过最大会话数，移除最旧的
+        let mut remaining = self.sessions.len() - removed.len();
+        for session in &candidates {
+            if remaining <= self.config.max_sessions {
                 break;
             }
-            
-            // 不要移除当前会话
-            if Some(&session.id) != self.current_session_id.as_ref() {
-                // self.sessions.remove(&session.id);
+            if removed.insert(&session.id) {
+                remaining -= 1;
             }
         }
+
+        // 超过总大小上限，继续移除最旧的
+        if let Some(max_mb) = self.config.max_sessions_size_mb {
+            let max_bytes = max_mb * 1024 * 1024;
+            let mut total: u64 = self
+                .sessions
+                .values()
+                .filter(|s| !removed.contains(s.id.as_str()))
+                .map(Session::size_bytes)
+                .sum();
+            for session in &candidates {
+                if total <= max_bytes {
+                    break;
+                }
+                if removed.insert(&session.id) {
+                    total -= session.size_bytes();
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|s| removed.contains(s.id.as_str()))
+            .collect()
+    }
+
+    // 执行保留策略，被移除的会话在保存时移入归档文件，返回被移除的会话ID
+    pub fn cleanup_old_sessions(&mut self) -> Vec<String> {
+        let ids: Vec<String> = self.plan_cleanup().iter().map(|s| s.id.clone()).collect();
+        self.archive_sessions(&ids, ArchiveReason::Retention);
+        ids
+    }
+
+    fn archive_sessions(&mut self, ids: &[String], reason: ArchiveReason) {
+        let now = Utc::now();
+        for id in ids {
+            if let Some(session) = self.sessions.remove(id) {
+                self.pending_archive.push(ArchivedSession {
+                    session,
+                    archived_at: now,
+                    reason,
+                });
+            }
+            self.recent_sessions.retain(|r| r != id);
+        }
     }
     
+    // 删除的会话移入回收站，可通过 restore_session 恢复
     pub fn remove_session(&mut self, session_id: &str) -> Result<(), SessionError> {
-        // 不能移除当前会话
+        if !self.sessions.contains_key(session_id) {
+            return Err(SessionError::SessionNotFound(session_id.to_string()));
+        }
+
         if Some(session_id) == self.current_session_id.as_deref() {
             self.current_session_id = None;
         }
-        
-        if self.sessions.remove(session_id).is_some() {
-            Ok(())
+
+        self.archive_sessions(&[session_id.to_string()], ArchiveReason::Deleted);
+        Ok(())
+    }
+
+    pub fn list_archived(&self) -> Result<Vec<ArchivedSession>, SessionError> {
+        let mut archive = load_archive(&self.archive_path)?;
+        archive.extend(self.pending_archive.iter().cloned());
+        archive.sort_by_key(|a| std::cmp::Reverse(a.archived_at));
+        Ok(archive)
+    }
+
+    // 从回收站/归档中恢复会话
+    pub fn restore_session(&mut self, session_id: &str) -> Result<(), SessionError> {
+        // 本次运行中移出的会话还未写入归档文件
+        let archived = if let Some(index) = self.pending_archive.iter().position(|a| a.session.id == session_id) {
+            self.pending_archive.remove(index)
         } else {
-            Err(SessionError::SessionNotFound(session_id.to_string()))
-        }
+            let mut archive = load_archive(&self.archive_path)?;
+            let index = archive
+                .iter()
+                .position(|a| a.session.id == session_id)
+                .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))?;
+            let archived = archive.remove(index);
+            save_archive(&self.archive_path, &archive)?;
+            archived
+        };
+
+        let mut session = archived.session;
+        session.ensure_message_ids();
+        session.last_accessed = Utc::now();
+        self.sessions.insert(session.id.clone(), session);
+        Ok(())
+    }
+
+    // 清空回收站/归档，返回被永久删除的会话数
+    pub fn empty_trash(&mut self) -> Result<usize, SessionError> {
+        let archive = load_archive(&self.archive_path)?;
+        save_archive(&self.archive_path, &[])?;
+        Ok(archive.len() + std::mem::take(&mut self.pending_archive).len())
     }
     
     pub fn rename_session(&mut self, session_id: &str, new_title: &str) -> Result<(), SessionError> {
@@ -218,23 +714,76 @@ impl SessionManager {
     }
     
     pub fn save_sessions(&self, path: &PathBuf) -> Result<(), SessionError> {
+        // 先写归档再写会话列表，写入失败时会话只会重复而不会丢失；
+        // 同一会话只保留最新的记录，重复保存不会产生重复条目
+        if !self.pending_archive.is_empty() {
+            let mut archive = load_archive(&self.archive_path)?;
+            archive.retain(|a| !self.pending_archive.iter().any(|p| p.session.id == a.session.id));
+            archive.extend(self.pending_archive.iter().cloned());
+            save_archive(&self.archive_path, &archive)?;
+        }
+
         let session_file = File::create(path)?;
         serde_json::to_writer_pretty(session_file, &self.sessions)?;
+
+        // 索引失败不影响会话保存
+        if let Err(e) = self.refresh_search_index() {
+            eprintln!("{} {}", warning_label(), t!("manager.search_index_failed", e));
+        }
+        self.save_state()
+    }
+
+    // 增量更新搜索索引，只重建发生变化的会话
+    pub fn refresh_search_index(&self) -> Result<SearchIndex, SessionError> {
+        let mut index = SearchIndex::load(&self.search_index_path).unwrap_or_default();
+        if index.update(&self.sessions) {
+            index.save(&self.search_index_path)?;
+        }
+        Ok(index)
+    }
+
+    pub fn save_state(&self) -> Result<(), SessionError> {
+        let state = SessionState {
+            current_session_id: self.current_session_id.clone(),
+            recent_sessions: self.recent_sessions.clone(),
+        };
+        let state_file = File::create(&self.state_path)?;
+        serde_json::to_writer_pretty(state_file, &state)?;
+        Ok(())
+    }
+
+    // 读取上一次进程保存的会话指针，需在 load_sessions 之后调用
+    pub fn load_state(&mut self) -> Result<(), SessionError> {
+        if !self.state_path.exists() {
+            return Ok(());
+        }
+
+        let state_file = File::open(&self.state_path)?;
+        let state: SessionState = serde_json::from_reader(state_file)?;
+
+        self.recent_sessions = state
+            .recent_sessions
+            .into_iter()
+            .filter(|id| self.sessions.contains_key(id))
+            .collect();
+        self.current_session_id = state
+            .current_session_id
+            .filter(|id| self.sessions.contains_key(id));
         Ok(())
     }
 
     pub async fn generate_session_file(&mut self, session_id: Option<&str>) -> Result<(), SessionError> {
         let mut file_parser = FileParser::new();
         if let Some(id) = session_id {
-            if let Some(session) = &self.sessions.get(&id.to_string()) {
-                if let Some(last_message) = session.messages.last() {
+            if let Some(session) = &self.sessions.get(id) {
+                if let Some(last_message) = session.active_branch().last() {
                     let _ = file_parser.init(last_message.content.clone()).await;
                 }
             } else {
                 return Err(SessionError::SessionNotFound(id.to_string()));
             }
         } else if let Some(session) = &self.get_current_session() {
-            if let Some(last_message) = session.messages.last() {
+            if let Some(last_message) = session.active_branch().last() {
                 let _ = file_parser.init(last_message.content.clone()).await;
             }
         } else {
@@ -247,12 +796,196 @@ impl SessionManager {
         if path.exists() {
             let session_file = File::open(path)?;
             self.sessions = serde_json::from_reader(session_file)?;
+            for session in self.sessions.values_mut() {
+                session.ensure_message_ids();
+            }
             Ok(())
         } else {
             Err(SessionError::IoError(io::Error::new(
                 io::ErrorKind::NotFound,
-                "会话文件不存在",
+                t!("manager.sessions_missing"),
             )))
         }
     }
 }
+
+// 按序号、完整ID、ID前缀、标题的顺序匹配，匹配到多个时返回候选列表
+fn resolve_among<'a>(
+    query: &str,
+    candidates: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<String, SessionError> {
+    let query = query.trim();
+    let candidates: Vec<(&str, &str)> = candidates.collect();
+    let not_found = || SessionError::SessionNotFound(query.to_string());
+
+    if let Some(index) = query.strip_prefix('#') {
+        let index: usize = index.parse().map_err(|_| not_found())?;
+        return index
+            .checked_sub(1)
+            .and_then(|i| candidates.get(i))
+            .map(|(id, _)| id.to_string())
+            .ok_or_else(not_found);
+    }
+
+    if let Some((id, _)) = candidates.iter().find(|(id, _)| *id == query) {
+        return Ok(id.to_string());
+    }
+
+    let by_prefix: Vec<&(&str, &str)> = if query.is_empty() {
+        Vec::new()
+    } else {
+        candidates.iter().filter(|(id, _)| id.starts_with(query)).collect()
+    };
+    let matches = if by_prefix.is_empty() {
+        candidates.iter().filter(|(_, title)| *title == query).collect()
+    } else {
+        by_prefix
+    };
+
+    match matches.as_slice() {
+        [] => Err(not_found()),
+        [(id, _)] => Ok(id.to_string()),
+        _ => Err(SessionError::AmbiguousSession(
+            query.to_string(),
+            matches
+                .iter()
+                .map(|(id, title)| format!("{} {}", &id[..8.min(id.len())], title))
+                .collect(),
+        )),
+    }
+}
+
+// 测试使用的临时数据目录
+#[cfg(test)]
+pub mod testing {
+    use super::SessionManager;
+    use std::path::PathBuf;
+
+    // 测试结束时删除临时目录
+    pub struct TempDir(pub PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    pub fn temp_manager(name: &str) -> (SessionManager, TempDir) {
+        let dir = std::env::temp_dir().join(format!("mobius-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manager = SessionManager::new(dir.join("config.json")).unwrap();
+        (manager, TempDir(dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::temp_manager;
+    use super::*;
+
+    // 插入一个 days_ago 天前访问过的会话
+    fn add(manager: &mut SessionManager, title: &str, days_ago: i64) -> String {
+        let mut session = Session::new(Some(title), "", None);
+        session.last_accessed = Utc::now() - Duration::days(days_ago);
+        let id = session.id.clone();
+        manager.sessions.insert(id.clone(), session);
+        id
+    }
+
+    fn planned(manager: &SessionManager) -> Vec<String> {
+        manager.plan_cleanup().iter().map(|s| s.title.clone()).collect()
+    }
+
+    #[test]
+    fn plan_cleanup_keeps_sessions_up_to_max() {
+        let (mut m, _dir) = temp_manager("cleanup-max");
+        m.config.max_sessions = 3;
+        for (title, days) in [("a", 3), ("b", 2), ("c", 1)] {
+            add(&mut m, title, days);
+        }
+        assert!(planned(&m).is_empty());
+
+        add(&mut m, "d", 0);
+        assert_eq!(planned(&m), ["a"]);
+    }
+
+    #[test]
+    fn plan_cleanup_skips_pinned_and_current() {
+        let (mut m, _dir) = temp_manager("cleanup-pinned");
+        m.config.max_sessions = 1;
+        let pinned = add(&mut m, "pinned", 5);
+        m.sessions.get_mut(&pinned).unwrap().pinned = true;
+        let current = add(&mut m, "current", 4);
+        m.current_session_id = Some(current);
+        add(&mut m, "old", 3);
+        add(&mut m, "new", 1);
+
+        // 只能移除未置顶的非当前会话，即使仍超过上限
+        assert_eq!(planned(&m), ["old", "new"]);
+    }
+
+    #[test]
+    fn plan_cleanup_age_limit_counts_toward_max() {
+        let (mut m, _dir) = temp_manager("cleanup-age");
+        m.config.max_sessions = 2;
+        m.config.max_session_age_days = Some(30);
+        add(&mut m, "expired", 31);
+        add(&mut m, "a", 29);
+        add(&mut m, "b", 2);
+        add(&mut m, "c", 1);
+
+        // 过期的会话已经让数量降到 3，只需再移除一个最旧的
+        assert_eq!(planned(&m), ["expired", "a"]);
+    }
+
+    #[test]
+    fn plan_cleanup_removes_oldest_until_under_size_limit() {
+        let (mut m, _dir) = temp_manager("cleanup-size");
+        m.config.max_sessions_size_mb = Some(1);
+        for (title, days) in [("a", 3), ("b", 2), ("c", 1)] {
+            let id = add(&mut m, title, days);
+            let session = m.sessions.get_mut(&id).unwrap();
+            session.add_message("user", &"x".repeat(400 * 1024));
+            // add_message 会刷新访问时间
+            session.last_accessed = Utc::now() - Duration::days(days);
+        }
+
+        assert_eq!(planned(&m), ["a"]);
+    }
+
+    const IDS: [(&str, &str); 3] = [
+        ("1a2b3c4d-0000", "周报"),
+        ("1a2b9999-0000", "草稿"),
+        ("5e6f7a8b-0000", "草稿"),
+    ];
+
+    fn resolve(query: &str) -> Result<String, SessionError> {
+        resolve_among(query, IDS.iter().copied())
+    }
+
+    #[test]
+    fn resolve_among_matches_index_id_prefix_and_title() {
+        assert_eq!(resolve("#3").unwrap(), "5e6f7a8b-0000");
+        assert_eq!(resolve("1a2b3c4d-0000").unwrap(), "1a2b3c4d-0000");
+        assert_eq!(resolve("5e6").unwrap(), "5e6f7a8b-0000");
+        assert_eq!(resolve(" 周报 ").unwrap(), "1a2b3c4d-0000");
+        assert!(matches!(resolve("#0"), Err(SessionError::SessionNotFound(_))));
+        assert!(matches!(resolve("#4"), Err(SessionError::SessionNotFound(_))));
+        assert!(matches!(resolve(""), Err(SessionError::SessionNotFound(_))));
+    }
+
+    #[test]
+    fn resolve_among_reports_ambiguous_prefix_and_title() {
+        match resolve("1a2b") {
+            Err(SessionError::AmbiguousSession(query, candidates)) => {
+                assert_eq!(query, "1a2b");
+                assert_eq!(candidates, ["1a2b3c4d 周报", "1a2b9999 草稿"]);
+            }
+            other => panic!("unexpected: {:?}", other),
+        }
+        match resolve("草稿") {
+            Err(SessionError::AmbiguousSession(_, candidates)) => assert_eq!(candidates.len(), 2),
+            other => panic!("unexpected: {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,27 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
+    // 旧版本保存的消息没有ID，加载后由 Session::ensure_message_ids 补齐
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub role: String,
     pub content: String,
     pub timestamp: DateTime<Utc>,
+}
+
+impl Message {
+    pub fn new(role: &str, content: &str, parent_id: Option<String>) -> Self {
+        Message {
+            id: Uuid::new_v4().to_string(),
+            parent_id,
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: Utc::now(),
+        }
+    }
 }
\ No newline at end of file
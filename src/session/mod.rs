@@ -1,5 +1,10 @@
 pub mod manager;
+pub mod archive;
+pub mod branch;
 pub mod config;
 pub mod theme;
 pub mod message;
-pub mod main_loop;
\ No newline at end of file
+pub mod main_loop;
+pub mod state;
+pub mod template;
+pub mod validate;
\ No newline at end of file
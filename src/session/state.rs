@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+// 最近会话列表保留的条目数
+pub const MAX_RECENT_SESSIONS: usize = 10;
+
+// 跨进程持久化的会话指针
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionState {
+    pub current_session_id: Option<String>,
+    // 最近使用的会话，最近的在前
+    #[serde(default)]
+    pub recent_sessions: Vec<String>,
+}
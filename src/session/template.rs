@@ -0,0 +1,175 @@
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::i18n::t;
+
+// 首次使用时写入模板目录的示例模板，使用当前界面语言
+fn default_templates() -> [(&'static str, &'static str); 4] {
+    [
+        ("review", t!("template.default_review")),
+        ("review-diff", t!("template.default_review_diff")),
+        ("translate", t!("template.default_translate")),
+        ("tests", t!("template.default_tests")),
+    ]
+}
+
+// 模板库：配置目录下 templates/<名称>.md
+pub struct TemplateLibrary {
+    pub dir: PathBuf,
+}
+
+impl TemplateLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        TemplateLibrary { dir }
+    }
+
+    fn ensure_dir(&self) -> io::Result<()> {
+        if !self.dir.exists() {
+            fs::create_dir_all(&self.dir)?;
+            for (name, content) in default_templates() {
+                fs::write(self.dir.join(format!("{}.md", name)), content)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        self.ensure_dir()?;
+        let mut names: Vec<String> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn load(&self, name: &str) -> Result<String> {
+        self.ensure_dir()?;
+        let path = self.dir.join(format!("{}.md", name));
+        fs::read_to_string(&path).with_context(|| t!("template.not_found", name))
+    }
+}
+
+// 模板中需要用户提供的变量，按出现顺序去重
+pub fn variables(template: &str) -> Vec<String> {
+    let re = Regex::new(r"\{\{\s*([A-Za-z_][\w-]*)\s*\}\}").unwrap();
+    let mut names: Vec<String> = Vec::new();
+    for captures in re.captures_iter(template) {
+        let name = captures[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+// 渲染模板：{{变量}}、{{file:路径}}、{{shell:命令}}，占位符可以嵌套，
+// 替换进来的内容不会再次被解析
+pub fn render(template: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = find_closing(after).ok_or_else(|| anyhow!(t!("template.unclosed")))?;
+        let expr = render(&after[..end], vars)?;
+        output.push_str(&evaluate(expr.trim(), vars)?);
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+// 找到与开头 {{ 匹配的 }} 的位置
+fn find_closing(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'{' {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'}' && bytes[i + 1] == b'}' {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn evaluate(expr: &str, vars: &HashMap<String, String>) -> Result<String> {
+    if let Some(path) = expr.strip_prefix("file:") {
+        let path = path.trim();
+        return fs::read_to_string(path).with_context(|| t!("files.read_failed", path));
+    }
+
+    if let Some(command) = expr.strip_prefix("shell:") {
+        let output = if cfg!(target_os = "windows") {
+            Command::new("cmd").args(["/C", command.trim()]).output()
+        } else {
+            Command::new("sh").arg("-c").arg(command.trim()).output()
+        }
+        .with_context(|| t!("template.spawn_failed", command))?;
+
+        if !output.status.success() {
+            bail!(t!(
+                "template.command_failed",
+                command,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string());
+    }
+
+    vars.get(expr)
+        .cloned()
+        .ok_or_else(|| anyhow!(t!("template.missing_variable", expr)))
+}
+
+// 解析 key=value 参数，值可以用引号包含空格
+pub fn parse_assignments(input: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for arg in split_args(input) {
+        if let Some((key, value)) = arg.split_once('=') {
+            vars.insert(key.to_string(), value.to_string());
+        }
+    }
+    vars
+}
+
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in input.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            None => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
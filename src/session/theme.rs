@@ -1,10 +1,340 @@
 use clap::ValueEnum;
+use crossterm::style::{self, Color, ContentStyle, StyledContent, Stylize};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::i18n::t;
+use crate::session::config::Config;
 
 // 主题枚举
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
 pub enum Theme {
     Light,
     Dark,
     System,
 }
+
+fn default_base() -> Theme {
+    Theme::System
+}
+
+// 用户自定义主题：在基础主题上覆盖部分颜色
+// 颜色可以是名称（red、dark_grey）、#rrggbb 或 0-255 的 ANSI 色号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomTheme {
+    pub name: String,
+    #[serde(default = "default_base")]
+    pub base: Theme,
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+}
+
+// 终端中各类文本的颜色
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub prompt: Color,
+    pub user: Color,
+    pub assistant: Color,
+    pub heading: Color,
+    pub accent: Color,
+    pub link: Color,
+    pub code: Color,
+    pub border: Color,
+    pub keyword: Color,
+    pub string: Color,
+    pub comment: Color,
+    pub number: Color,
+    pub function: Color,
+    pub type_name: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub notice: Color,
+}
+
+impl Palette {
+    pub fn dark() -> Self {
+        Palette {
+            prompt: Color::Green,
+            user: Color::Cyan,
+            assistant: Color::Reset,
+            heading: Color::Magenta,
+            accent: Color::Yellow,
+            link: Color::Blue,
+            code: Color::Cyan,
+            border: Color::DarkGrey,
+            keyword: Color::Magenta,
+            string: Color::Green,
+            comment: Color::DarkGrey,
+            number: Color::Yellow,
+            function: Color::Blue,
+            type_name: Color::Cyan,
+            error: Color::Red,
+            warning: Color::Yellow,
+            notice: Color::DarkGrey,
+        }
+    }
+
+    pub fn light() -> Self {
+        Palette {
+            prompt: Color::DarkGreen,
+            user: Color::DarkBlue,
+            assistant: Color::Reset,
+            heading: Color::DarkMagenta,
+            accent: Color::DarkYellow,
+            link: Color::DarkBlue,
+            code: Color::DarkCyan,
+            border: Color::Grey,
+            keyword: Color::DarkMagenta,
+            string: Color::DarkGreen,
+            comment: Color::Grey,
+            number: Color::DarkYellow,
+            function: Color::DarkBlue,
+            type_name: Color::DarkCyan,
+            error: Color::DarkRed,
+            warning: Color::DarkYellow,
+            notice: Color::Grey,
+        }
+    }
+
+    // System 主题根据终端背景选择浅色或深色
+    pub fn for_theme(theme: Theme) -> Self {
+        match resolve_system(theme) {
+            Theme::Light => Palette::light(),
+            _ => Palette::dark(),
+        }
+    }
+
+    fn slot(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "prompt" => &mut self.prompt,
+            "user" => &mut self.user,
+            "assistant" => &mut self.assistant,
+            "heading" => &mut self.heading,
+            "accent" => &mut self.accent,
+            "link" => &mut self.link,
+            "code" => &mut self.code,
+            "border" => &mut self.border,
+            "keyword" => &mut self.keyword,
+            "string" => &mut self.string,
+            "comment" => &mut self.comment,
+            "number" => &mut self.number,
+            "function" => &mut self.function,
+            "type" => &mut self.type_name,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "notice" => &mut self.notice,
+            _ => return None,
+        })
+    }
+
+    // 应用自定义颜色，返回无法识别的条目
+    pub fn with_overrides(mut self, colors: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut invalid = Vec::new();
+        for (role, value) in colors {
+            match (self.slot(role), parse_color(value)) {
+                (Some(slot), Some(color)) => *slot = color,
+                _ => invalid.push(format!("{} = {}", role, value)),
+            }
+        }
+        (self, invalid)
+    }
+}
+
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+    if let Ok(index) = value.parse::<u8>() {
+        return Some(Color::AnsiValue(index));
+    }
+    Color::try_from(value).ok()
+}
+
+// System 主题：先看 COLORFGBG，再向终端查询背景色，都失败时按深色处理
+fn resolve_system(theme: Theme) -> Theme {
+    if theme != Theme::System {
+        return theme;
+    }
+    background_from_env()
+        .or_else(query_background)
+        .unwrap_or(Theme::Dark)
+}
+
+// COLORFGBG 形如 "15;0"，最后一项是背景色号
+fn background_from_env() -> Option<Theme> {
+    let value = env::var("COLORFGBG").ok()?;
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+    Some(match background {
+        7 | 9..=15 => Theme::Light,
+        _ => Theme::Dark,
+    })
+}
+
+// 通过 OSC 11 查询终端背景色，终端不支持时在超时后放弃
+#[cfg(unix)]
+fn query_background() -> Option<Theme> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::io::{self, IsTerminal, Write};
+
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return None;
+    }
+
+    enable_raw_mode().ok()?;
+    let reply = (|| {
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x1b]11;?\x07").ok()?;
+        stdout.flush().ok()?;
+
+        let mut reply = Vec::new();
+        let mut buf = [0u8; 64];
+        loop {
+            let mut fd = libc::pollfd {
+                fd: libc::STDIN_FILENO,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: fd 指向栈上有效的 pollfd，数量为 1
+            let ready = unsafe { libc::poll(&mut fd, 1, 100) };
+            if ready <= 0 {
+                return None;
+            }
+            // 直接读取文件描述符，不经过 Stdin 的缓冲，以免吞掉后续的用户输入
+            // SAFETY: buf 在整个调用期间有效，长度与传入的一致
+            let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+            if n <= 0 {
+                return None;
+            }
+            reply.extend_from_slice(&buf[..n as usize]);
+            if reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\") || reply.len() > 128 {
+                return Some(String::from_utf8_lossy(&reply).into_owned());
+            }
+        }
+    })();
+    let _ = disable_raw_mode();
+
+    // 回复形如 ESC ] 11 ; rgb:RRRR/GGGG/BBBB BEL
+    let rgb = reply?.split("rgb:").nth(1)?.to_string();
+    let channels: Vec<f64> = rgb
+        .trim_end_matches(['\x07', '\x1b', '\\'])
+        .split('/')
+        .map(|c| {
+            let max = 16f64.powi(c.len() as i32) - 1.0;
+            u32::from_str_radix(c, 16).ok().map(|v| v as f64 / max)
+        })
+        .collect::<Option<_>>()?;
+    let [r, g, b] = channels[..] else {
+        return None;
+    };
+    let luminance = 0.299 * r + 0.587 * g + 0.114 * b;
+    Some(if luminance > 0.5 { Theme::Light } else { Theme::Dark })
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<Theme> {
+    None
+}
+
+struct Selection {
+    theme: Theme,
+    custom: Option<CustomTheme>,
+}
+
+static SELECTION: OnceLock<Selection> = OnceLock::new();
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+static STDOUT_STYLED: AtomicBool = AtomicBool::new(true);
+static STDERR_STYLED: AtomicBool = AtomicBool::new(true);
+
+// 输出不是终端、设置了 NO_COLOR 或使用 plain/json 输出格式时不输出颜色和样式；
+// 需在解析命令行之后、输出任何内容之前调用
+pub fn init_styling(machine_output: bool) {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || machine_output {
+        // 全屏界面等直接使用颜色的地方也不再输出颜色
+        style::force_color_output(false);
+    }
+    let enabled = !no_color && !machine_output;
+    STDOUT_STYLED.store(enabled && io::stdout().is_terminal(), Ordering::Relaxed);
+    STDERR_STYLED.store(enabled && io::stderr().is_terminal(), Ordering::Relaxed);
+}
+
+pub fn stdout_styled() -> bool {
+    STDOUT_STYLED.load(Ordering::Relaxed)
+}
+
+pub fn stderr_styled() -> bool {
+    STDERR_STYLED.load(Ordering::Relaxed)
+}
+
+// 记录配置中选择的主题，调色板在第一次使用时生成，避免不需要颜色的命令查询终端
+pub fn init(config: &Config) {
+    let custom = config
+        .custom_theme
+        .as_deref()
+        .and_then(|name| config.custom_themes.iter().find(|t| t.name == name))
+        .cloned();
+    let _ = SELECTION.set(Selection {
+        theme: config.theme,
+        custom,
+    });
+}
+
+pub fn palette() -> &'static Palette {
+    PALETTE.get_or_init(|| {
+        let Some(selection) = SELECTION.get() else {
+            return Palette::dark();
+        };
+        match &selection.custom {
+            Some(custom) => {
+                let (palette, invalid) = Palette::for_theme(custom.base).with_overrides(&custom.colors);
+                for entry in invalid {
+                    eprintln!("{} {}", t!("label.warning"), t!("theme.invalid_color", custom.name, entry));
+                }
+                palette
+            }
+            None => Palette::for_theme(selection.theme),
+        }
+    })
+}
+
+// 错误和警告输出到标准错误
+pub fn error_label() -> StyledContent<&'static str> {
+    if !stderr_styled() {
+        return StyledContent::new(ContentStyle::default(), t!("label.error"));
+    }
+    t!("label.error").with(palette().error).bold()
+}
+
+pub fn warning_label() -> StyledContent<&'static str> {
+    if !stderr_styled() {
+        return StyledContent::new(ContentStyle::default(), t!("label.warning"));
+    }
+    t!("label.warning").with(palette().warning).bold()
+}
+
+// 系统提示信息（会话信息、附件、引用等），可能输出到标准输出或标准错误，
+// 两者都是终端时才着色
+pub fn notice<D: Display>(text: D) -> StyledContent<D> {
+    if !stdout_styled() || !stderr_styled() {
+        return StyledContent::new(ContentStyle::default(), text);
+    }
+    let style = ContentStyle {
+        foreground_color: Some(palette().notice),
+        ..ContentStyle::default()
+    };
+    StyledContent::new(style, text)
+}
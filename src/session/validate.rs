@@ -0,0 +1,574 @@
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::error::Category;
+use serde_json::Value;
+use std::fmt;
+
+use crate::i18n::t;
+use crate::session::config::{expand_home, lookup_path, lookup_path_mut, Config, Model};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// 配置检查发现的一个问题，field 为点号路径，line 从 1 开始
+#[derive(Debug, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub field: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.field.is_empty(), self.line) {
+            (true, Some(line)) => write!(f, "{}: {}", t!("check.line", line), self.message),
+            (false, Some(line)) => write!(f, "{} ({}): {}", self.field, t!("check.line", line), self.message),
+            (false, None) => write!(f, "{}: {}", self.field, self.message),
+            (true, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// 检查配置文件原文，返回解析出的配置（有错误时为 None）和发现的问题
+pub fn check_config(content: &str) -> (Option<Config>, Vec<Issue>) {
+    if let Err(e) = serde_json::from_str::<Value>(content) {
+        let issue = Issue {
+            severity: Severity::Error,
+            field: String::new(),
+            line: Some(e.line()),
+            message: error_message(&e),
+        };
+        return (None, vec![issue]);
+    }
+
+    let spans = Scanner::scan(content);
+    match serde_json::from_str::<Config>(content) {
+        Ok(config) => {
+            let issues = check_values(&config, &spans, content);
+            (Some(config), issues)
+        }
+        Err(e) => {
+            let field = error_path(content, &spans, &e).unwrap_or_default();
+            let issue = Issue {
+                severity: Severity::Error,
+                field,
+                line: Some(e.line()),
+                message: error_message(&e),
+            };
+            (None, vec![issue])
+        }
+    }
+}
+
+impl Issue {
+    // 只影响请求接口的问题（key 缺失或无法读取），不请求接口的命令不必提示
+    pub fn is_request_only(&self) -> bool {
+        let name = self.field.rsplit('.').next().unwrap_or_default();
+        self.severity == Severity::Warning && matches!(name, "api_key" | "api_key_env" | "api_key_file")
+    }
+}
+
+// 检查修改后的配置，只返回修改前没有的问题；检查的不是配置文件原文，line 为空
+pub fn check_change(before: &Config, after: &Config) -> Vec<Issue> {
+    let issues = |config: &Config| {
+        let content = config.to_json().and_then(|value| serde_json::to_string_pretty(&value)).unwrap_or_default();
+        check_config(&content).1
+    };
+    let existing = issues(before);
+    issues(after)
+        .into_iter()
+        .filter(|issue| !existing.iter().any(|e| e.field == issue.field && e.message == issue.message))
+        .map(|issue| Issue { line: None, ..issue })
+        .collect()
+}
+
+// 用 Config::default() 补全缺失的字段，替换类型不符或无效（如地址无法解析）的字段；
+// 原文不是合法的 JSON 时只能使用默认配置
+pub fn repair_config(content: &str) -> Config {
+    let Ok(user) = serde_json::from_str::<Value>(content) else {
+        return Config::default();
+    };
+    let Ok(default) = Config::default().to_json() else {
+        return Config::default();
+    };
+    let mut merged = merge(default.clone(), user);
+    let mut repaired = None;
+
+    // 逐步替换出错的字段，直到能够解析且没有错误
+    for _ in 0..64 {
+        let Ok(text) = serde_json::to_string_pretty(&merged) else {
+            break;
+        };
+        let fields = match serde_json::from_str::<Config>(&text) {
+            Ok(config) => {
+                let fields: Vec<String> = check_values(&config, &[], &text)
+                    .into_iter()
+                    .filter(|issue| issue.severity == Severity::Error)
+                    .map(|issue| issue.field)
+                    .collect();
+                repaired = Some(config);
+                fields
+            }
+            Err(e) => error_path(&text, &Scanner::scan(&text), &e).into_iter().collect(),
+        };
+        if fields.is_empty() {
+            break;
+        }
+
+        let mut changed = false;
+        for field in fields {
+            changed |= match default_at(&default, &field) {
+                Some(value) => replace_at(&mut merged, &field, value),
+                // 没有默认值可用时删除出错的数组元素或字段
+                None => remove_at(&mut merged, &field),
+            };
+        }
+        if !changed {
+            break;
+        }
+    }
+    repaired.unwrap_or_default()
+}
+
+// 模型的地址和密钥，角色、主题引用的名称
+fn check_values(config: &Config, spans: &[Span], content: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut push = |severity, field: String, message: String| {
+        let line = spans
+            .iter()
+            .find(|s| s.path == field)
+            .map(|s| line_at(content, s.start));
+        issues.push(Issue { severity, field, line, message });
+    };
+
+    let models = std::iter::once(("default_model".to_string(), &config.default_model)).chain(
+        config
+            .models
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, model)| (format!("models.{}", i), model)),
+    );
+    for (path, model) in models {
+        check_model(&path, model, &mut push);
+    }
+
+    let mut seen = Vec::new();
+    for (i, model) in config.all_models().enumerate().skip(1) {
+        let name = model.display_name();
+        if seen.contains(&name) || name == config.default_model.display_name() {
+            push(Severity::Warning, format!("models.{}.name", i - 1), t!("check.duplicate_model", name));
+        }
+        seen.push(name);
+    }
+
+    for (i, persona) in config.personas.iter().enumerate() {
+        if let Some(model) = &persona.model
+            && config.find_model(model).is_none()
+        {
+            push(Severity::Warning, format!("personas.{}.model", i), t!("check.unknown_model", model));
+        }
+        for (field, value) in [("temperature", persona.temperature), ("top_p", persona.top_p)] {
+            let max = if field == "temperature" { 2.0 } else { 1.0 };
+            if let Some(value) = value
+                && !(0.0..=max).contains(&value)
+            {
+                push(
+                    Severity::Warning,
+                    format!("personas.{}.{}", i, field),
+                    t!("check.out_of_range", value, 0, max),
+                );
+            }
+        }
+    }
+    if let Some(name) = &config.default_persona
+        && config.find_persona(name).is_none()
+    {
+        push(Severity::Warning, "default_persona".to_string(), t!("check.unknown_persona", name));
+    }
+    if let Some(name) = &config.custom_theme
+        && !config.custom_themes.iter().any(|t| &t.name == name)
+    {
+        push(Severity::Warning, "custom_theme".to_string(), t!("check.unknown_theme", name));
+    }
+    if config.max_sessions == 0 {
+        push(Severity::Error, "max_sessions".to_string(), t!("check.zero_max_sessions").to_string());
+    }
+    issues
+}
+
+fn check_model(path: &str, model: &Model, push: &mut impl FnMut(Severity, String, String)) {
+    match Url::parse(&model.api_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        Ok(url) => push(
+            Severity::Error,
+            format!("{}.api_url", path),
+            t!("check.unsupported_scheme", url.scheme()),
+        ),
+        Err(e) => push(Severity::Error, format!("{}.api_url", path), t!("check.invalid_url", e)),
+    }
+    // 不运行 api_key_cmd，只检查环境变量和文件是否存在
+    if let Some(name) = &model.api_key_env {
+        if std::env::var_os(name).is_none() {
+            push(Severity::Warning, format!("{}.api_key_env", path), t!("check.key_env_unset", name));
+        }
+    } else if let Some(file) = &model.api_key_file {
+        if !expand_home(file).is_file() {
+            push(Severity::Warning, format!("{}.api_key_file", path), t!("check.key_file_missing", file.display()));
+        }
+    } else if model.api_key_cmd.is_none() && model.api_key.trim().is_empty() {
+        push(Severity::Warning, format!("{}.api_key", path), t!("check.empty_api_key").to_string());
+    }
+    if model.model.trim().is_empty() {
+        push(Severity::Error, format!("{}.model", path), t!("check.empty_model").to_string());
+    }
+}
+
+// serde_json 的错误信息末尾带有 "at line L column C"，位置单独显示
+fn error_message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    match message.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => message,
+    }
+}
+
+// 根据错误位置找到出错的字段；缺少字段时为所在对象的路径加上字段名
+fn error_path(content: &str, spans: &[Span], e: &serde_json::Error) -> Option<String> {
+    if matches!(e.classify(), Category::Syntax | Category::Eof) {
+        return None;
+    }
+    let offset = offset_of(content, e.line(), e.column());
+    let containing = spans.iter().filter(|s| s.start <= offset && offset <= s.end);
+
+    let message = e.to_string();
+    if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+    {
+        let object = containing.filter(|s| s.is_object).max_by_key(|s| s.depth)?;
+        return Some(join(&object.path, field));
+    }
+    containing
+        .filter(|s| !s.path.is_empty())
+        .max_by_key(|s| s.depth)
+        .map(|s| s.path.clone())
+}
+
+fn offset_of(content: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(content.len())
+}
+
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+// 对象逐个字段合并，其他值以用户的配置为准
+fn merge(default: Value, user: Value) -> Value {
+    match (default, user) {
+        (Value::Object(mut default), Value::Object(user)) => {
+            for (key, value) in user {
+                let merged = match default.remove(&key) {
+                    Some(default) => merge(default, value),
+                    None => value,
+                };
+                default.insert(key, merged);
+            }
+            Value::Object(default)
+        }
+        (_, user) => user,
+    }
+}
+
+// 默认配置中对应字段的值；models 中的模型参照 default_model，personas 参照第一个角色
+fn default_at(default: &Value, path: &str) -> Option<Value> {
+    if let Some(value) = lookup_path(default, path) {
+        return Some(value.clone());
+    }
+    let mut parts = path.splitn(3, '.');
+    let (list, _, rest) = (parts.next()?, parts.next()?, parts.next()?);
+    let template = match list {
+        "models" => "default_model".to_string(),
+        "personas" => "personas.0".to_string(),
+        _ => return None,
+    };
+    lookup_path(default, &join(&template, rest)).cloned()
+}
+
+// 值没有变化时返回 false
+fn replace_at(root: &mut Value, path: &str, value: Value) -> bool {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (lookup_path_mut(root, parent), key),
+        None => (Some(root), path),
+    };
+    match parent {
+        Some(Value::Object(map)) => map.insert(key.to_string(), value.clone()) != Some(value),
+        Some(Value::Array(items)) => match key.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+            Some(item) if *item != value => {
+                *item = value;
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// 删除路径上最近的数组元素，不在数组中时删除字段本身
+fn remove_at(root: &mut Value, path: &str) -> bool {
+    let parts: Vec<&str> = path.split('.').collect();
+    for end in (1..parts.len()).rev() {
+        let parent = parts[..end].join(".");
+        if let Some(Value::Array(items)) = lookup_path_mut(root, &parent)
+            && let Ok(index) = parts[end].parse::<usize>()
+            && index < items.len()
+        {
+            items.remove(index);
+            return true;
+        }
+    }
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (lookup_path_mut(root, parent), key),
+        None => (Some(root), path),
+    };
+    match parent {
+        Some(Value::Object(map)) => map.remove(key).is_some(),
+        _ => false,
+    }
+}
+
+// 一个 JSON 值在原文中的范围
+struct Span {
+    path: String,
+    depth: usize,
+    start: usize,
+    end: usize,
+    is_object: bool,
+}
+
+// 记录原文中每个值的路径和位置，用于把错误位置对应到字段
+struct Scanner<'a> {
+    content: &'a str,
+    pos: usize,
+    spans: Vec<Span>,
+}
+
+impl<'a> Scanner<'a> {
+    fn scan(content: &'a str) -> Vec<Span> {
+        let mut scanner = Scanner {
+            content,
+            pos: 0,
+            spans: Vec::new(),
+        };
+        scanner.value(String::new(), 0);
+        scanner.spans
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.content.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, path: String, depth: usize) {
+        self.skip_whitespace();
+        let start = self.pos;
+        let is_object = self.peek() == Some(b'{');
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        break;
+                    }
+                    let key = self.string();
+                    self.skip_whitespace();
+                    if self.peek() != Some(b':') {
+                        break;
+                    }
+                    self.pos += 1;
+                    self.value(join(&path, &key), depth + 1);
+                    self.skip_whitespace();
+                    if self.peek() != Some(b',') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    if matches!(self.peek(), Some(b']') | None) {
+                        break;
+                    }
+                    self.value(join(&path, &index.to_string()), depth + 1);
+                    index += 1;
+                    self.skip_whitespace();
+                    if self.peek() != Some(b',') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                }
+            }
+            Some(b'"') => {
+                self.string();
+            }
+            Some(_) => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !b.is_ascii_whitespace() && !matches!(b, b',' | b'}' | b']'))
+                {
+                    self.pos += 1;
+                }
+            }
+            None => return,
+        }
+        self.spans.push(Span {
+            path,
+            depth,
+            start,
+            end: self.pos,
+            is_object,
+        });
+    }
+
+    // 读取一个字符串，返回转义后的内容
+    fn string(&mut self) -> String {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            match b {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos = (self.pos + 1).min(self.content.len());
+        let raw = &self.content[start..self.pos];
+        serde_json::from_str(raw).unwrap_or_else(|_| raw.trim_matches('"').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"{
+  "max_sessions": 10,
+  "auto_save": true,
+  "theme": "Dark",
+  "models": null,
+  "default_model": {
+    "api_key": "sk-abcdefghijklmn",
+    "api_url": "https://api.example.com",
+    "model": "chat"
+  }
+}"#;
+
+    fn check(content: &str) -> Vec<(Severity, String, Option<usize>)> {
+        check_config(content)
+            .1
+            .into_iter()
+            .map(|issue| (issue.severity, issue.field, issue.line))
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_issues() {
+        let (config, issues) = check_config(BASE);
+        assert!(config.is_some());
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn syntax_error_reports_line_without_field() {
+        let content = BASE.replace("\"auto_save\": true,", "\"auto_save\": true,,");
+        assert_eq!(check(&content), [(Severity::Error, String::new(), Some(3))]);
+    }
+
+    #[test]
+    fn type_error_maps_to_field_and_line() {
+        let content = BASE.replace("\"max_sessions\": 10", "\"max_sessions\": \"many\"");
+        assert_eq!(check(&content), [(Severity::Error, "max_sessions".to_string(), Some(2))]);
+    }
+
+    #[test]
+    fn missing_field_is_joined_to_its_object() {
+        let content = BASE.replace("    \"model\": \"chat\"\n", "").replace("example.com\",", "example.com\"");
+        let issues = check(&content);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].1, "default_model.model");
+
+        let content = BASE.replace("  \"auto_save\": true,\n", "");
+        assert_eq!(check(&content)[0].1, "auto_save");
+    }
+
+    #[test]
+    fn value_issues_point_at_the_offending_line() {
+        let content = BASE
+            .replace("https://api.example.com", "ftp://api.example.com")
+            .replace("sk-abcdefghijklmn", "");
+        assert_eq!(
+            check(&content),
+            [
+                (Severity::Error, "default_model.api_url".to_string(), Some(8)),
+                (Severity::Warning, "default_model.api_key".to_string(), Some(7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_change_reports_only_new_issues_without_lines() {
+        let before: Config = serde_json::from_str(&BASE.replace("sk-abcdefghijklmn", "")).unwrap();
+        let after = before.set_value("default_model.api_url", "not a url").unwrap();
+        let issues: Vec<_> = check_change(&before, &after)
+            .into_iter()
+            .map(|issue| (issue.severity, issue.field, issue.line))
+            .collect();
+        assert_eq!(issues, [(Severity::Error, "default_model.api_url".to_string(), None)]);
+    }
+
+    #[test]
+    fn only_key_warnings_are_request_only() {
+        let content = BASE.replace("sk-abcdefghijklmn", "").replace("\"max_sessions\": 10", "\"max_sessions\": 0");
+        let issues = check_config(&content).1;
+        let request_only: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.is_request_only())
+            .map(|issue| issue.field.as_str())
+            .collect();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(request_only, ["default_model.api_key"]);
+    }
+}
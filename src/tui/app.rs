@@ -0,0 +1,301 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Stylize;
+
+use crate::context::project::estimate_tokens;
+use crate::i18n::t;
+use crate::markdown::render::render_markdown;
+use crate::models::deepseek::TokenUsage;
+use crate::session::manager::SessionManager;
+use crate::session::theme::palette;
+use crate::tui::editor::InputBuffer;
+
+pub struct SessionEntry {
+    pub id: String,
+    pub title: String,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Focus {
+    Input,
+    Sidebar,
+}
+
+pub enum Mode {
+    Normal,
+    Rename { id: String, buffer: InputBuffer },
+    ConfirmDelete { id: String, title: String },
+}
+
+// 按键产生的操作，由主循环交给 SessionManager 执行
+pub enum Action {
+    None,
+    Send(String),
+    NewSession,
+    Switch(String),
+    Rename(String, String),
+    Delete(String),
+    Quit,
+}
+
+// 状态栏显示的信息
+#[derive(Default)]
+pub struct Status {
+    pub model: String,
+    pub persona: Option<String>,
+    pub context_tokens: usize,
+    pub last_usage: Option<TokenUsage>,
+}
+
+// 全屏界面的状态；绘制只依赖这里的快照，生成回复时不需要访问 SessionManager
+pub struct App {
+    pub sessions: Vec<SessionEntry>,
+    pub current: Option<String>,
+    pub selected: usize,
+    pub focus: Focus,
+    pub mode: Mode,
+    pub input: InputBuffer,
+    // 已按对话区宽度渲染好的对话内容
+    pub conversation: Vec<String>,
+    // 正在生成的回复
+    pub streaming: Option<String>,
+    // 距离底部滚动的行数，0 表示跟随最新内容
+    pub scroll: usize,
+    pub status: Status,
+    // 状态栏中的提示，第二项表示是否为错误
+    pub message: Option<(String, bool)>,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new() -> Self {
+        App {
+            sessions: Vec::new(),
+            current: None,
+            selected: 0,
+            focus: Focus::Input,
+            mode: Mode::Normal,
+            input: InputBuffer::new(),
+            conversation: Vec::new(),
+            streaming: None,
+            scroll: 0,
+            status: Status::default(),
+            message: None,
+            quit: false,
+        }
+    }
+
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.message = Some((message.into(), false));
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.message = Some((message.into(), true));
+    }
+
+    // 从 SessionManager 刷新会话列表、对话内容和状态栏
+    pub fn sync(&mut self, session_manager: &mut SessionManager, width: usize) {
+        self.sessions = session_manager
+            .list_sessions()
+            .into_iter()
+            .map(|s| SessionEntry {
+                id: s.id.clone(),
+                title: s.title.clone(),
+                pinned: s.pinned,
+            })
+            .collect();
+        self.current = session_manager.current_session_id.clone();
+        if self.focus == Focus::Input
+            && let Some(index) = self.sessions.iter().position(|s| Some(&s.id) == self.current.as_ref())
+        {
+            self.selected = index;
+        }
+        self.selected = self.selected.min(self.sessions.len().saturating_sub(1));
+
+        self.status.model = session_manager.current_model().display_name().to_string();
+        self.status.persona = session_manager.current_persona().map(|p| p.name.clone());
+        self.status.last_usage = session_manager.last_usage;
+
+        self.conversation.clear();
+        self.status.context_tokens = 0;
+        let Some(session) = session_manager.get_current_session() else {
+            return;
+        };
+        for message in session.active_branch() {
+            self.status.context_tokens += estimate_tokens(&message.content);
+            if message.role == "system" {
+                continue;
+            }
+            self.conversation.push(role_header(&message.role));
+            self.conversation.extend(render_markdown(&message.content, width));
+            self.conversation.push(String::new());
+        }
+    }
+
+    // 包括正在生成的回复在内的全部对话行
+    pub fn conversation_lines(&self, width: usize) -> Vec<String> {
+        let mut lines = self.conversation.clone();
+        if let Some(streaming) = &self.streaming {
+            lines.push(role_header("assistant"));
+            lines.extend(render_markdown(streaming, width));
+        }
+        lines
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        match &mut self.mode {
+            Mode::Rename { buffer, .. } => buffer.insert_str(&text.replace('\n', " ")),
+            _ => self.input.insert_str(text),
+        }
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll += lines;
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, page: usize) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if ctrl && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q')) {
+            return Action::Quit;
+        }
+
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Rename { id, mut buffer } => {
+                match key.code {
+                    KeyCode::Enter if !buffer.is_empty() => {
+                        return Action::Rename(id, buffer.text().trim().to_string());
+                    }
+                    KeyCode::Esc => return Action::None,
+                    _ => edit(&mut buffer, key),
+                }
+                self.mode = Mode::Rename { id, buffer };
+                return Action::None;
+            }
+            Mode::ConfirmDelete { id, .. } => {
+                return match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => Action::Delete(id),
+                    _ => {
+                        self.notify(t!("tui.delete_cancelled"));
+                        Action::None
+                    }
+                };
+            }
+            Mode::Normal => {}
+        }
+
+        match key.code {
+            KeyCode::PageUp => self.scroll_up(page),
+            KeyCode::PageDown => self.scroll_down(page),
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => self.scroll_up(1),
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => self.scroll_down(1),
+            KeyCode::Char('n') if ctrl => return Action::NewSession,
+            KeyCode::F(2) => self.start_rename(),
+            _ if self.focus == Focus::Sidebar => return self.handle_sidebar_key(key),
+            _ => return self.handle_input_key(key),
+        }
+        Action::None
+    }
+
+    fn selected_entry(&self) -> Option<&SessionEntry> {
+        match self.focus {
+            Focus::Sidebar => self.sessions.get(self.selected),
+            Focus::Input => self.sessions.iter().find(|s| Some(&s.id) == self.current.as_ref()),
+        }
+    }
+
+    fn start_rename(&mut self) {
+        if let Some(entry) = self.selected_entry() {
+            self.mode = Mode::Rename {
+                id: entry.id.clone(),
+                buffer: InputBuffer::with_text(&entry.title),
+            };
+        }
+    }
+
+    fn handle_sidebar_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.sessions.len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                self.focus = Focus::Input;
+                if let Some(entry) = self.sessions.get(self.selected) {
+                    return Action::Switch(entry.id.clone());
+                }
+            }
+            KeyCode::Char('n') => return Action::NewSession,
+            KeyCode::Char('r') => self.start_rename(),
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(entry) = self.sessions.get(self.selected) {
+                    self.mode = Mode::ConfirmDelete {
+                        id: entry.id.clone(),
+                        title: entry.title.clone(),
+                    };
+                }
+            }
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Tab | KeyCode::Esc => self.focus = Focus::Input,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Action {
+        let newline = key.modifiers.contains(KeyModifiers::ALT)
+            || key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Tab => self.focus = Focus::Sidebar,
+            KeyCode::Enter if newline => self.input.insert('\n'),
+            KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.insert('\n')
+            }
+            KeyCode::Enter => {
+                if !self.input.is_empty() {
+                    self.scroll = 0;
+                    return Action::Send(self.input.take());
+                }
+            }
+            _ => edit(&mut self.input, key),
+        }
+        Action::None
+    }
+}
+
+// 输入框通用的编辑按键
+fn edit(buffer: &mut InputBuffer, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('a') if ctrl => buffer.home(),
+        KeyCode::Char('e') if ctrl => buffer.end(),
+        KeyCode::Char('u') if ctrl => buffer.delete_to_line_start(),
+        KeyCode::Char('w') if ctrl => buffer.delete_word(),
+        KeyCode::Char(c) if !ctrl => buffer.insert(c),
+        KeyCode::Backspace => buffer.backspace(),
+        KeyCode::Delete => buffer.delete(),
+        KeyCode::Left => buffer.left(),
+        KeyCode::Right => buffer.right(),
+        KeyCode::Home => buffer.home(),
+        KeyCode::End => buffer.end(),
+        KeyCode::Up => {
+            buffer.up();
+        }
+        KeyCode::Down => {
+            buffer.down();
+        }
+        _ => {}
+    }
+}
+
+fn role_header(role: &str) -> String {
+    let palette = palette();
+    match role {
+        "user" => format!("{}", format!("▍{}", t!("tui.you")).with(palette.user).bold()),
+        "assistant" => format!("{}", format!("▍{}", t!("tui.assistant")).with(palette.heading).bold()),
+        other => format!("{}", format!("▍{}", other).with(palette.notice).bold()),
+    }
+}
@@ -0,0 +1,172 @@
+use unicode_width::UnicodeWidthChar;
+
+// 全屏界面的多行输入框
+#[derive(Debug, Default)]
+pub struct InputBuffer {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_text(text: &str) -> Self {
+        let mut buffer = Self::new();
+        buffer.insert_str(text);
+        buffer
+    }
+
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.iter().all(|c| c.is_whitespace())
+    }
+
+    pub fn take(&mut self) -> String {
+        let text = self.text();
+        self.chars.clear();
+        self.cursor = 0;
+        text
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    // 粘贴的文本统一换行符，去掉制表符以外的控制字符
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.replace("\r\n", "\n").replace('\r', "\n").chars() {
+            if c == '\n' || c == '\t' || !c.is_control() {
+                self.insert(c);
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    fn line_start(&self, pos: usize) -> usize {
+        self.chars[..pos]
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+
+    fn line_end(&self, pos: usize) -> usize {
+        self.chars[pos..]
+            .iter()
+            .position(|&c| c == '\n')
+            .map(|i| pos + i)
+            .unwrap_or(self.chars.len())
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = self.line_start(self.cursor);
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.line_end(self.cursor);
+    }
+
+    // 上下移动到相邻的逻辑行，尽量保持列位置；已在首行/末行时返回 false
+    pub fn up(&mut self) -> bool {
+        let start = self.line_start(self.cursor);
+        if start == 0 {
+            return false;
+        }
+        let column = self.cursor - start;
+        let previous = self.line_start(start - 1);
+        self.cursor = (previous + column).min(start - 1);
+        true
+    }
+
+    pub fn down(&mut self) -> bool {
+        let end = self.line_end(self.cursor);
+        if end == self.chars.len() {
+            return false;
+        }
+        let column = self.cursor - self.line_start(self.cursor);
+        let next_end = self.line_end(end + 1);
+        self.cursor = (end + 1 + column).min(next_end);
+        true
+    }
+
+    // Ctrl-W：删除光标前的一个单词
+    pub fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    // Ctrl-U：删除到行首
+    pub fn delete_to_line_start(&mut self) {
+        let start = self.line_start(self.cursor);
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    // 按宽度折行后的显示行，以及光标所在的行和列
+    pub fn layout(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let width = width.max(1);
+        let mut rows = vec![String::new()];
+        let mut column = 0;
+        let mut cursor = (0, 0);
+
+        for (i, &c) in self.chars.iter().enumerate() {
+            let w = if c == '\t' { 1 } else { c.width().unwrap_or(0) };
+            if c != '\n' && column + w > width {
+                rows.push(String::new());
+                column = 0;
+            }
+            if i == self.cursor {
+                cursor = (rows.len() - 1, column);
+            }
+            if c == '\n' {
+                rows.push(String::new());
+                column = 0;
+            } else {
+                rows.last_mut().unwrap().push(if c == '\t' { ' ' } else { c });
+                column += w;
+            }
+        }
+
+        if self.cursor == self.chars.len() {
+            if column >= width {
+                rows.push(String::new());
+                column = 0;
+            }
+            cursor = (rows.len() - 1, column);
+        }
+        (rows, cursor)
+    }
+}
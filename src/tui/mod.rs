@@ -0,0 +1,199 @@
+pub mod app;
+pub mod editor;
+mod view;
+
+use crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEventKind, KeyModifiers,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::i18n::t;
+use crate::context::files::{collect_attachments, compose_message, extract_mentions};
+use crate::models::model::generate_response_with;
+use crate::session::manager::SessionManager;
+use crate::session::theme::palette;
+use crate::tui::app::{Action, App};
+use crate::tui::view::{Layout, draw};
+
+// 生成回复时两次重绘之间的最短间隔
+const REDRAW_INTERVAL: Duration = Duration::from_millis(30);
+
+// 进入全屏模式，离开作用域时（包括出错和 panic）恢复终端
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+pub async fn run_tui(
+    session_manager: &mut SessionManager,
+    sessions_path: &PathBuf,
+) -> Result<(), Box<dyn Error>> {
+    if session_manager.current_session_id.is_none() {
+        session_manager.create_session(None);
+    }
+
+    // 进入备用屏幕前初始化配色，无效颜色的警告不会打乱界面
+    palette();
+    session_manager.warnings = Some(Vec::new());
+    let guard = TerminalGuard::enter()?;
+    let mut app = App::new();
+    refresh(&mut app, session_manager);
+
+    while !app.quit {
+        draw(&app)?;
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                let page = Layout::new(&app).conversation_height.max(1);
+                let action = app.handle_key(key, page);
+                apply(action, &mut app, session_manager).await;
+            }
+            Event::Paste(text) => app.paste(&text),
+            Event::Resize(_, _) => refresh(&mut app, session_manager),
+            _ => {}
+        }
+    }
+    drop(guard);
+    session_manager.warnings = None;
+
+    if session_manager.config.auto_save {
+        session_manager.save_sessions(sessions_path)?;
+    } else {
+        session_manager.save_state()?;
+    }
+    Ok(())
+}
+
+// 按当前窗口宽度重新渲染对话
+fn refresh(app: &mut App, session_manager: &mut SessionManager) {
+    let width = Layout::new(app).main_width;
+    app.sync(session_manager, width);
+    // 生成过程中的警告显示在状态栏
+    if let Some(warnings) = &mut session_manager.warnings
+        && !warnings.is_empty()
+    {
+        app.error(std::mem::take(warnings).join("; "));
+    }
+}
+
+async fn apply(action: Action, app: &mut App, session_manager: &mut SessionManager) {
+    match action {
+        Action::None => {}
+        Action::Quit => app.quit = true,
+        Action::Send(input) => send(input, app, session_manager).await,
+        Action::NewSession => {
+            let id = session_manager.create_session(None).to_string();
+            app.notify(t!("tui.session_created", &id[..8]));
+        }
+        Action::Switch(id) => match session_manager.switch_session(&id) {
+            Ok(_) => app.message = None,
+            Err(e) => app.error(e.to_string()),
+        },
+        Action::Rename(id, title) => match session_manager.rename_session(&id, &title) {
+            Ok(_) => app.notify(t!("tui.renamed", title)),
+            Err(e) => app.error(e.to_string()),
+        },
+        Action::Delete(id) => match session_manager.remove_session(&id) {
+            Ok(_) => {
+                // 删除当前会话后切换到列表中的第一个会话，没有会话时新建一个
+                if session_manager.current_session_id.is_none() {
+                    let next = session_manager.list_sessions().first().map(|s| s.id.clone());
+                    match next {
+                        Some(next) => {
+                            let _ = session_manager.switch_session(&next);
+                        }
+                        None => {
+                            session_manager.create_session(None);
+                        }
+                    }
+                }
+                app.notify(t!("tui.trashed"));
+            }
+            Err(e) => app.error(e.to_string()),
+        },
+    }
+    refresh(app, session_manager);
+}
+
+async fn send(input: String, app: &mut App, session_manager: &mut SessionManager) {
+    // @路径 形式的附件和 /file 添加的附件一起发送
+    let (text, mentions) = extract_mentions(input.trim());
+    let (attachments, warnings) = collect_attachments(&mentions);
+    let mut pending = std::mem::take(&mut session_manager.pending_attachments);
+    pending.extend(attachments);
+    let content = compose_message(&text, &pending);
+    if let Some(session) = session_manager.get_current_session() {
+        session.add_message("user", &content);
+    }
+    if !warnings.is_empty() {
+        app.error(warnings.join("; "));
+    } else if !pending.is_empty() {
+        app.notify(t!("tui.attached", pending.len()));
+    } else {
+        app.message = None;
+    }
+    refresh(app, session_manager);
+
+    app.streaming = Some(String::new());
+    let mut last_draw = Instant::now();
+    let mut cancelled = false;
+    let result = generate_response_with(session_manager, |delta| {
+        if let Some(streaming) = &mut app.streaming {
+            streaming.push_str(delta);
+        }
+        // 生成过程中只响应取消，其余按键丢弃
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && (key.code == KeyCode::Esc
+                    || key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.code == KeyCode::Char('c'))
+            {
+                cancelled = true;
+                return Err(io::Error::from(io::ErrorKind::Interrupted));
+            }
+        }
+        if last_draw.elapsed() >= REDRAW_INTERVAL {
+            last_draw = Instant::now();
+            draw(app)?;
+        }
+        Ok(())
+    })
+    .await;
+
+    let partial = app.streaming.take().unwrap_or_default();
+    match result {
+        Ok(citations) if !citations.is_empty() => {
+            app.notify(t!("reply.citations", citations.join(", ")));
+        }
+        Ok(_) => {}
+        // 取消时保留已经生成的部分
+        Err(_) if cancelled => {
+            if !partial.is_empty()
+                && let Some(session) = session_manager.get_current_session()
+            {
+                session.add_message("assistant", &partial);
+            }
+            app.notify(t!("tui.generation_cancelled"));
+        }
+        Err(e) => app.error(e.to_string()),
+    }
+}
@@ -0,0 +1,210 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::queue;
+use crossterm::style::{Print, Stylize};
+use crossterm::terminal::{self, BeginSynchronizedUpdate, EndSynchronizedUpdate};
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthChar;
+
+use crate::i18n::t;
+use crate::session::theme::palette;
+use crate::tui::app::{App, Focus, Mode};
+
+// 输入框最多显示的行数，更多内容在框内滚动
+const MAX_INPUT_ROWS: usize = 8;
+
+// 界面各区域的位置和大小
+pub struct Layout {
+    pub width: usize,
+    pub height: usize,
+    pub sidebar_width: usize,
+    pub main_x: usize,
+    pub main_width: usize,
+    pub conversation_height: usize,
+    pub input_top: usize,
+    pub input_rows: usize,
+}
+
+impl Layout {
+    pub fn new(app: &App) -> Self {
+        let (width, height) = terminal::size().map(|(w, h)| (w as usize, h as usize)).unwrap_or((80, 24));
+        // 窗口太窄时隐藏会话列表
+        let sidebar_width = if width >= 60 { (width / 4).clamp(16, 32) } else { 0 };
+        let main_x = if sidebar_width > 0 { sidebar_width + 1 } else { 0 };
+        let main_width = width.saturating_sub(main_x).max(1);
+        let (rows, _) = app.input.layout(main_width);
+        let input_rows = rows.len().clamp(1, MAX_INPUT_ROWS);
+        // 底部依次是输入框的分隔线、输入行和状态栏
+        let conversation_height = height.saturating_sub(input_rows + 2);
+        Layout {
+            width,
+            height,
+            sidebar_width,
+            main_x,
+            main_width,
+            conversation_height,
+            input_top: conversation_height + 1,
+            input_rows,
+        }
+    }
+}
+
+// 按显示宽度截断或补齐一行，保留其中的 ANSI 转义序列
+pub fn fit(line: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            if chars.peek() == Some(&'[') {
+                out.extend(chars.next());
+                for n in chars.by_ref() {
+                    out.push(n);
+                    if ('@'..='~').contains(&n) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        let w = if c == '\t' { 1 } else { c.width().unwrap_or(0) };
+        if used + w > width {
+            break;
+        }
+        out.push(if c == '\t' { ' ' } else { c });
+        used += w;
+    }
+    // 补齐的空格沿用行末的样式，选中行和状态栏的反色可以铺满整行
+    out.push_str(&" ".repeat(width - used));
+    out.push_str("\x1b[0m");
+    out
+}
+
+pub fn draw(app: &App) -> io::Result<()> {
+    let layout = Layout::new(app);
+    let palette = palette();
+    let mut out = io::stdout().lock();
+    queue!(out, BeginSynchronizedUpdate, Hide)?;
+
+    // 会话列表
+    if layout.sidebar_width > 0 {
+        let rows = layout.height.saturating_sub(1);
+        let header = t!("tui.sessions", app.sessions.len());
+        let header = if app.focus == Focus::Sidebar {
+            format!("{}", header.with(palette.accent).bold())
+        } else {
+            format!("{}", header.bold())
+        };
+        queue!(out, MoveTo(0, 0), Print(fit(&header, layout.sidebar_width)))?;
+
+        let visible = rows.saturating_sub(1);
+        let offset = (app.selected + 1).saturating_sub(visible);
+        for row in 0..visible {
+            let line = match app.sessions.get(offset + row) {
+                Some(entry) => {
+                    let marker = if Some(&entry.id) == app.current.as_ref() { "›" } else { " " };
+                    let pin = if entry.pinned { "*" } else { "" };
+                    let text = fit(&format!("{}{}{}", marker, pin, entry.title), layout.sidebar_width);
+                    if offset + row == app.selected && app.focus == Focus::Sidebar {
+                        format!("{}", text.reverse())
+                    } else if Some(&entry.id) == app.current.as_ref() {
+                        format!("{}", text.bold())
+                    } else {
+                        text
+                    }
+                }
+                None => fit("", layout.sidebar_width),
+            };
+            queue!(out, MoveTo(0, (row + 1) as u16), Print(line))?;
+        }
+        for row in 0..rows {
+            queue!(
+                out,
+                MoveTo(layout.sidebar_width as u16, row as u16),
+                Print("│".with(palette.border))
+            )?;
+        }
+    }
+
+    // 对话区，scroll 为 0 时显示最新的内容
+    let lines = app.conversation_lines(layout.main_width);
+    let max_scroll = lines.len().saturating_sub(layout.conversation_height);
+    let scroll = app.scroll.min(max_scroll);
+    let end = lines.len() - scroll;
+    let start = end.saturating_sub(layout.conversation_height);
+    for row in 0..layout.conversation_height {
+        let line = lines.get(start + row).map(String::as_str).unwrap_or("");
+        queue!(
+            out,
+            MoveTo(layout.main_x as u16, row as u16),
+            Print(fit(line, layout.main_width))
+        )?;
+    }
+
+    // 输入框
+    let title = match &app.mode {
+        Mode::Rename { .. } => t!("tui.rename_title"),
+        _ if app.streaming.is_some() => t!("tui.streaming_title"),
+        _ => t!("tui.input_title"),
+    };
+    let border = format!("──{}{}", title, "─".repeat(layout.main_width));
+    queue!(
+        out,
+        MoveTo(layout.main_x as u16, (layout.input_top - 1) as u16),
+        Print(fit(&format!("{}", border.with(palette.border)), layout.main_width))
+    )?;
+
+    let buffer = match &app.mode {
+        Mode::Rename { buffer, .. } => buffer,
+        _ => &app.input,
+    };
+    let (rows, (cursor_row, cursor_col)) = buffer.layout(layout.main_width);
+    let input_offset = (cursor_row + 1).saturating_sub(layout.input_rows);
+    for row in 0..layout.input_rows {
+        let text = rows.get(input_offset + row).map(String::as_str).unwrap_or("");
+        let text = format!("{}", text.with(palette.user));
+        queue!(
+            out,
+            MoveTo(layout.main_x as u16, (layout.input_top + row) as u16),
+            Print(fit(&text, layout.main_width))
+        )?;
+    }
+
+    // 状态栏
+    let status = &app.status;
+    let mut left = format!(" {} ", status.model);
+    if let Some(persona) = &status.persona {
+        left.push_str(&t!("tui.persona", persona));
+    }
+    left.push_str(&t!("tui.context", status.context_tokens));
+    if let Some(usage) = status.last_usage {
+        left.push_str(&t!("tui.usage", usage.prompt_tokens, usage.completion_tokens));
+    }
+    let right = match (&app.mode, &app.message) {
+        (Mode::ConfirmDelete { title, .. }, _) => t!("tui.confirm_delete", title),
+        (_, Some((message, _))) => message.clone(),
+        _ if app.focus == Focus::Sidebar => t!("tui.sidebar_hints").to_string(),
+        _ => t!("tui.hints").to_string(),
+    };
+    let status_line = fit(&format!("{}| {}", left, right), layout.width);
+    let status_line = match &app.message {
+        Some((_, true)) => format!("{}", status_line.with(palette.error).reverse()),
+        _ => format!("{}", status_line.reverse()),
+    };
+    queue!(out, MoveTo(0, (layout.height - 1) as u16), Print(status_line))?;
+
+    if matches!(app.mode, Mode::ConfirmDelete { .. }) || app.focus == Focus::Sidebar {
+        queue!(out, EndSynchronizedUpdate)?;
+    } else {
+        queue!(
+            out,
+            MoveTo(
+                (layout.main_x + cursor_col) as u16,
+                (layout.input_top + cursor_row - input_offset) as u16
+            ),
+            Show,
+            EndSynchronizedUpdate
+        )?;
+    }
+    out.flush()
+}